@@ -0,0 +1,23 @@
+// Copyright Ryangguk Kim @ Oak Bioinformatics, LLC
+//
+// This software is available under a dual licensing model, offering users the choice between the Affero General Public License version 3 (AGPL-3) for open-source use and a commercial license for proprietary or commercial use.
+//
+// To obtain a commercial license, please contact info@oakbioinformatics.com.
+
+//! With the `header` feature, regenerates `include/spdi.h` from `src/capi.rs`.
+
+fn main() {
+    #[cfg(feature = "header")]
+    {
+        println!("cargo:rerun-if-changed=src/capi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir)).unwrap();
+        cbindgen::Builder::new()
+            .with_crate(&crate_dir)
+            .with_config(config)
+            .generate()
+            .expect("cannot generate the C header")
+            .write_to_file(format!("{}/include/spdi.h", crate_dir));
+    }
+}
@@ -0,0 +1,59 @@
+#ifndef SPDI_H
+#define SPDI_H
+
+/* Generated from src/capi.rs by `cargo build --features header`. Do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// A loaded 2bit or FASTA reference.
+typedef struct SpdiReference SpdiReference;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Opens a 2bit or uncompressed FASTA file. Returns NULL on failure.
+//
+// # Safety
+// `path` must be NULL or a NUL-terminated string.
+struct SpdiReference *spdi_reference_open(const char *path);
+
+// Frees a reference from `spdi_reference_open`. NULL is ignored.
+//
+// # Safety
+// `reference` must be NULL or a reference which has not been freed.
+void spdi_reference_free(struct SpdiReference *reference);
+
+// Returns the SPDI of a variant with a 1-based `pos`, such as
+// `chr1:99092:C:CT`, or NULL on failure. Empty alleles can be given as an
+// empty string or `-`. The result is freed with `spdi_string_free`.
+//
+// # Safety
+// `reference` must be NULL or a reference which has not been freed, and the
+// strings must be NULL or NUL-terminated.
+char *spdi_convert(const struct SpdiReference *reference,
+                   const char *chrom,
+                   size_t pos,
+                   const char *ref_base,
+                   const char *alt_base);
+
+// Frees a string from `spdi_convert`. NULL is ignored.
+//
+// # Safety
+// `s` must be NULL or a string from `spdi_convert` which has not been freed.
+void spdi_string_free(char *s);
+
+// Returns the message of the last error on this thread, or NULL if there has
+// been none. The message is valid until the next failing call on the thread
+// and must not be freed.
+const char *spdi_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SPDI_H */
@@ -0,0 +1,160 @@
+// Copyright Ryangguk Kim @ Oak Bioinformatics, LLC
+//
+// This software is available under a dual licensing model, offering users the choice between the Affero General Public License version 3 (AGPL-3) for open-source use and a commercial license for proprietary or commercial use.
+//
+// To obtain a commercial license, please contact info@oakbioinformatics.com.
+
+//! C API, declared in `include/spdi.h`.
+//!
+//! ```c
+//! SpdiReference *reference = spdi_reference_open("hg38.2bit");
+//! if (reference == NULL) {
+//!     fprintf(stderr, "%s\n", spdi_last_error());
+//! }
+//! char *spdi = spdi_convert(reference, "chr1", 99092, "C", "CT");
+//! if (spdi == NULL) {
+//!     fprintf(stderr, "%s\n", spdi_last_error());
+//! }
+//! spdi_string_free(spdi);
+//! spdi_reference_free(reference);
+//! ```
+//!
+//! A reference can be shared by threads. Errors are kept per thread.
+
+use crate::contig::ContigResolver;
+use crate::SPDI;
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::PathBuf;
+
+/// A loaded 2bit or FASTA reference.
+pub struct SpdiReference {
+    spdi: SPDI,
+    resolver: ContigResolver,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    // Messages come from Rust strings, which can only hold NUL as a character.
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+/// Runs `f`, keeping its error as the last error of the thread. Panics are
+/// errors too, as they must not unwind into C.
+fn call<T>(f: impl FnOnce() -> Result<T, String>) -> Option<T> {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(v)) => Some(v),
+        Ok(Err(e)) => {
+            set_last_error(e);
+            None
+        }
+        Err(_) => {
+            set_last_error("Internal error".to_string());
+            None
+        }
+    }
+}
+
+/// # Safety
+/// `s` must be NULL or a NUL-terminated string.
+unsafe fn get_str<'a>(s: *const c_char, name: &str) -> Result<&'a str, String> {
+    if s.is_null() {
+        return Err(format!("{} is NULL", name));
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|_| format!("{} is not UTF-8", name))
+}
+
+/// Opens a 2bit or uncompressed FASTA file. Returns NULL on failure.
+///
+/// # Safety
+/// `path` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn spdi_reference_open(path: *const c_char) -> *mut SpdiReference {
+    call(|| {
+        let path = PathBuf::from(get_str(path, "path")?);
+        if !path.exists() {
+            return Err(format!("No such file: {}", path.display()));
+        }
+        let spdi = SPDI::new(&path).map_err(|e| e.to_string())?;
+        let resolver = spdi.contig_resolver();
+        Ok(Box::into_raw(Box::new(SpdiReference { spdi, resolver })))
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
+
+/// Frees a reference from `spdi_reference_open`. NULL is ignored.
+///
+/// # Safety
+/// `reference` must be NULL or a reference which has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn spdi_reference_free(reference: *mut SpdiReference) {
+    if !reference.is_null() {
+        drop(Box::from_raw(reference));
+    }
+}
+
+/// Returns the SPDI of a variant with a 1-based `pos`, such as
+/// `chr1:99092:C:CT`, or NULL on failure. Empty alleles can be given as an
+/// empty string or `-`. The result is freed with `spdi_string_free`.
+///
+/// # Safety
+/// `reference` must be NULL or a reference which has not been freed, and the
+/// strings must be NULL or NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn spdi_convert(
+    reference: *const SpdiReference,
+    chrom: *const c_char,
+    pos: usize,
+    ref_base: *const c_char,
+    alt_base: *const c_char,
+) -> *mut c_char {
+    call(|| {
+        let reference = reference.as_ref().ok_or("reference is NULL")?;
+        let chrom = get_str(chrom, "chrom")?;
+        // Only - itself is an empty allele; other bases are checked as given.
+        let ref_base = match get_str(ref_base, "ref")? {
+            "-" => "",
+            v => v,
+        };
+        let alt_base = match get_str(alt_base, "alt")? {
+            "-" => "",
+            v => v,
+        };
+        let new_chrom = reference.resolver.resolve(chrom).unwrap_or(chrom.to_string());
+        let (new_pos, new_ref, new_alt) = reference
+            .spdi
+            .get_spdi_string_components_str(new_chrom.as_bytes(), pos, ref_base.as_bytes(), alt_base.as_bytes())
+            .map_err(|e| e.to_string())?;
+        let spdi_string = format!("{}:{}:{}:{}", new_chrom, new_pos, new_ref, new_alt);
+        CString::new(spdi_string)
+            .map(CString::into_raw)
+            .map_err(|e| e.to_string())
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
+
+/// Frees a string from `spdi_convert`. NULL is ignored.
+///
+/// # Safety
+/// `s` must be NULL or a string from `spdi_convert` which has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn spdi_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Returns the message of the last error on this thread, or NULL if there has
+/// been none. The message is valid until the next failing call on the thread
+/// and must not be freed.
+#[no_mangle]
+pub extern "C" fn spdi_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(std::ptr::null(), |v| v.as_ptr()))
+}
@@ -0,0 +1,95 @@
+// Copyright Ryangguk Kim @ Oak Bioinformatics, LLC
+//
+// This software is available under a dual licensing model, offering users the choice between the Affero General Public License version 3 (AGPL-3) for open-source use and a commercial license for proprietary or commercial use.
+//
+// To obtain a commercial license, please contact info@oakbioinformatics.com.
+
+//! Classes of variants, from their reference and alternate bases without the
+//! bases both share at either end.
+
+use crate::repeat::TandemRepeat;
+
+/// Class of a variant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum VariantClass {
+    /// The alternate bases are the reference bases.
+    Identity,
+    /// One base replaced by another.
+    Snv,
+    /// Several bases replaced by as many others.
+    Mnv,
+    Insertion,
+    Deletion,
+    /// Bases replaced by a different number of bases.
+    Delins,
+}
+
+impl VariantClass {
+    /// Returns the class of a variant with `ref_len` reference and `alt_len`
+    /// alternate bases after trimming.
+    pub fn of_lens(ref_len: usize, alt_len: usize) -> VariantClass {
+        match (ref_len, alt_len) {
+            (0, 0) => VariantClass::Identity,
+            (1, 1) => VariantClass::Snv,
+            (0, _) => VariantClass::Insertion,
+            (_, 0) => VariantClass::Deletion,
+            (r, a) if r == a => VariantClass::Mnv,
+            _ => VariantClass::Delins,
+        }
+    }
+
+    /// Lower-case name, such as `snv` or `delins`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VariantClass::Identity => "identity",
+            VariantClass::Snv => "snv",
+            VariantClass::Mnv => "mnv",
+            VariantClass::Insertion => "insertion",
+            VariantClass::Deletion => "deletion",
+            VariantClass::Delins => "delins",
+        }
+    }
+
+    /// Insertions and deletions are the classes whose SPDI can span more
+    /// reference bases than the variant.
+    pub fn is_indel(&self) -> bool {
+        matches!(self, VariantClass::Insertion | VariantClass::Deletion)
+    }
+}
+
+impl std::fmt::Display for VariantClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// SPDI components of a variant with its class.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClassifiedSpdi {
+    pub pos: usize,
+    pub ref_bases: String,
+    pub alt_bases: String,
+    pub class: VariantClass,
+    /// True if the variant is an indel which can be placed at more than one
+    /// position, so that its SPDI spans more reference bases than the indel.
+    pub shiftable: bool,
+    /// Repeat which a shiftable indel changes.
+    pub repeat: Option<TandemRepeat>,
+    /// How far normalization moved the ends of the variant.
+    pub shifts: NormalizationShifts,
+}
+
+/// Numbers of bases by which normalization moved the ends of a variant.
+///
+/// Bases shared by both alleles are trimmed first from the end and then from
+/// the start. A trimmed indel is then grown over the reference bases it can be
+/// placed at, to the left and to the right. The grown bases are the window in
+/// which callers may place the indel, so `grown_left` is how far the input is
+/// from the leftmost placement.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NormalizationShifts {
+    pub trimmed_left: usize,
+    pub trimmed_right: usize,
+    pub grown_left: usize,
+    pub grown_right: usize,
+}
@@ -0,0 +1,72 @@
+// Copyright Ryangguk Kim @ Oak Bioinformatics, LLC
+//
+// This software is available under a dual licensing model, offering users the choice between the Affero General Public License version 3 (AGPL-3) for open-source use and a commercial license for proprietary or commercial use.
+//
+// To obtain a commercial license, please contact info@oakbioinformatics.com.
+
+//! SPDIs which give the bases of indels as a repeated motif and a length, like
+//! the ReferenceLengthExpression of GA4GH VRS.
+//!
+//! The SPDI of an indel covers the repeat the indel can move in, so both of its
+//! alleles are copies of one motif and can be stored in constant space.
+
+/// Bases of an allele.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CompactSequence {
+    /// Bases as they are.
+    Literal(String),
+    /// `len` bases of `motif` repeated from its first base. The last copy can
+    /// be partial.
+    Repeat { motif: String, len: usize },
+}
+
+impl CompactSequence {
+    /// Number of bases.
+    pub fn len(&self) -> usize {
+        match self {
+            CompactSequence::Literal(bases) => bases.len(),
+            CompactSequence::Repeat { len, .. } => *len,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Whole copies of the motif, or `None` for literal bases and an empty
+    /// motif.
+    pub fn copies(&self) -> Option<usize> {
+        match self {
+            CompactSequence::Literal(_) => None,
+            CompactSequence::Repeat { motif, len } => len.checked_div(motif.len()),
+        }
+    }
+
+    /// Returns the bases, which are empty for an empty allele.
+    pub fn expand(&self) -> String {
+        match self {
+            CompactSequence::Literal(bases) => bases.clone(),
+            CompactSequence::Repeat { motif, len } => motif.chars().cycle().take(*len).collect(),
+        }
+    }
+}
+
+/// SPDI of a variant with the 1-based position of its first deleted base.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompactSpdi {
+    pub pos: usize,
+    pub deleted: CompactSequence,
+    pub inserted: CompactSequence,
+}
+
+impl CompactSpdi {
+    /// Returns the reference and alternate bases as
+    /// `SPDI::get_spdi_string_components` does, with `-` for an empty allele.
+    pub fn expand(&self) -> (String, String) {
+        let expand = |sequence: &CompactSequence| match sequence.is_empty() {
+            true => "-".to_string(),
+            false => sequence.expand(),
+        };
+        (expand(&self.deleted), expand(&self.inserted))
+    }
+}
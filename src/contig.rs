@@ -0,0 +1,816 @@
+// Copyright Ryangguk Kim @ Oak Bioinformatics, LLC
+//
+// This software is available under a dual licensing model, offering users the choice between the Affero General Public License version 3 (AGPL-3) for open-source use and a commercial license for proprietary or commercial use.
+//
+// To obtain a commercial license, please contact info@oakbioinformatics.com.
+
+//! Contig names in UCSC, Ensembl, GenBank and RefSeq styles for GRCh38, and
+//! resolution of input contig names to sequence names in a reference.
+
+use ahash::{HashMap, HashSet};
+use lazy_static::lazy_static;
+use std::io::BufRead;
+use std::path::Path;
+
+/// Naming styles of reference sequences.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContigNaming {
+    /// `chr1`, `chrM`, `chr14_GL000009v2_random`
+    Ucsc,
+    /// `1`, `MT`, `GL000009.2`
+    Ensembl,
+    /// `CM000663.2`, `J01415.2`, `GL000009.2`
+    GenBank,
+    /// `NC_000001.11`, `NC_012920.1`
+    RefSeq,
+}
+
+/// GRCh38 primary assembly sequences as (UCSC, Ensembl, GenBank, RefSeq).
+pub static PRIMARY_CONTIGS: [(&str, &str, &str, &str); 25] = [
+    ("chr1", "1", "CM000663.2", "NC_000001.11"),
+    ("chr2", "2", "CM000664.2", "NC_000002.12"),
+    ("chr3", "3", "CM000665.2", "NC_000003.12"),
+    ("chr4", "4", "CM000666.2", "NC_000004.12"),
+    ("chr5", "5", "CM000667.2", "NC_000005.10"),
+    ("chr6", "6", "CM000668.2", "NC_000006.12"),
+    ("chr7", "7", "CM000669.2", "NC_000007.14"),
+    ("chr8", "8", "CM000670.2", "NC_000008.11"),
+    ("chr9", "9", "CM000671.2", "NC_000009.12"),
+    ("chr10", "10", "CM000672.2", "NC_000010.11"),
+    ("chr11", "11", "CM000673.2", "NC_000011.10"),
+    ("chr12", "12", "CM000674.2", "NC_000012.12"),
+    ("chr13", "13", "CM000675.2", "NC_000013.11"),
+    ("chr14", "14", "CM000676.2", "NC_000014.9"),
+    ("chr15", "15", "CM000677.2", "NC_000015.10"),
+    ("chr16", "16", "CM000678.2", "NC_000016.10"),
+    ("chr17", "17", "CM000679.2", "NC_000017.11"),
+    ("chr18", "18", "CM000680.2", "NC_000018.10"),
+    ("chr19", "19", "CM000681.2", "NC_000019.10"),
+    ("chr20", "20", "CM000682.2", "NC_000020.11"),
+    ("chr21", "21", "CM000683.2", "NC_000021.9"),
+    ("chr22", "22", "CM000684.2", "NC_000022.11"),
+    ("chrX", "X", "CM000685.2", "NC_000023.11"),
+    ("chrY", "Y", "CM000686.2", "NC_000024.10"),
+    ("chrM", "MT", "J01415.2", "NC_012920.1"),
+];
+
+/// Lengths of the GRCh38 primary assembly sequences in the order of
+/// [`PRIMARY_CONTIGS`].
+pub static PRIMARY_LENGTHS: [usize; 25] = [
+    248956422, 242193529, 198295559, 190214555, 181538259, 170805979, 159345973, 145138636,
+    138394717, 133797422, 135086622, 133275309, 114364328, 107043718, 101991189, 90338345,
+    83257441, 80373285, 58617616, 64444167, 46709983, 50818468, 156040895, 57227415,
+    RCRS_LENGTH,
+];
+
+lazy_static! {
+    /// UCSC names of GRCh38 alt and random sequences keyed by their accessions.
+    pub static ref UCSC_NAMES: HashMap<&'static str, &'static str> = {
+        let mut m = HashMap::default();
+        m.insert("KI270721.1", "chr11_KI270721v1_random");
+        m.insert("GL000009.2", "chr14_GL000009v2_random");
+        m.insert("GL000225.1", "chr14_GL000225v1_random");
+        m.insert("KI270722.1", "chr14_KI270722v1_random");
+        m.insert("GL000194.1", "chr14_GL000194v1_random");
+        m.insert("KI270723.1", "chr14_KI270723v1_random");
+        m.insert("KI270724.1", "chr14_KI270724v1_random");
+        m.insert("KI270725.1", "chr14_KI270725v1_random");
+        m.insert("KI270726.1", "chr14_KI270726v1_random");
+        m.insert("KI270727.1", "chr15_KI270727v1_random");
+        m.insert("KI270728.1", "chr16_KI270728v1_random");
+        m.insert("GL000205.2", "chr17_GL000205v2_random");
+        m.insert("KI270729.1", "chr17_KI270729v1_random");
+        m.insert("KI270730.1", "chr17_KI270730v1_random");
+        m.insert("KI270706.1", "chr1_KI270706v1_random");
+        m.insert("KI270707.1", "chr1_KI270707v1_random");
+        m.insert("KI270708.1", "chr1_KI270708v1_random");
+        m.insert("KI270709.1", "chr1_KI270709v1_random");
+        m.insert("KI270710.1", "chr1_KI270710v1_random");
+        m.insert("KI270711.1", "chr1_KI270711v1_random");
+        m.insert("KI270712.1", "chr1_KI270712v1_random");
+        m.insert("KI270713.1", "chr1_KI270713v1_random");
+        m.insert("KI270714.1", "chr1_KI270714v1_random");
+        m.insert("KI270731.1", "chr22_KI270731v1_random");
+        m.insert("KI270732.1", "chr22_KI270732v1_random");
+        m.insert("KI270733.1", "chr22_KI270733v1_random");
+        m.insert("KI270734.1", "chr22_KI270734v1_random");
+        m.insert("KI270735.1", "chr22_KI270735v1_random");
+        m.insert("KI270736.1", "chr22_KI270736v1_random");
+        m.insert("KI270737.1", "chr22_KI270737v1_random");
+        m.insert("KI270738.1", "chr22_KI270738v1_random");
+        m.insert("KI270739.1", "chr22_KI270739v1_random");
+        m.insert("KI270715.1", "chr2_KI270715v1_random");
+        m.insert("KI270716.1", "chr2_KI270716v1_random");
+        m.insert("GL000221.1", "chr3_GL000221v1_random");
+        m.insert("GL000008.2", "chr4_GL000008v2_random");
+        m.insert("GL000208.1", "chr5_GL000208v1_random");
+        m.insert("KI270717.1", "chr9_KI270717v1_random");
+        m.insert("KI270718.1", "chr9_KI270718v1_random");
+        m.insert("KI270719.1", "chr9_KI270719v1_random");
+        m.insert("KI270720.1", "chr9_KI270720v1_random");
+        m.insert("KI270762.1", "chr1_KI270762v1_alt");
+        m.insert("KI270766.1", "chr1_KI270766v1_alt");
+        m.insert("KI270760.1", "chr1_KI270760v1_alt");
+        m.insert("KI270765.1", "chr1_KI270765v1_alt");
+        m.insert("GL383518.1", "chr1_GL383518v1_alt");
+        m.insert("GL383519.1", "chr1_GL383519v1_alt");
+        m.insert("GL383520.2", "chr1_GL383520v2_alt");
+        m.insert("KI270764.1", "chr1_KI270764v1_alt");
+        m.insert("KI270763.1", "chr1_KI270763v1_alt");
+        m.insert("KI270759.1", "chr1_KI270759v1_alt");
+        m.insert("KI270761.1", "chr1_KI270761v1_alt");
+        m.insert("KI270770.1", "chr2_KI270770v1_alt");
+        m.insert("KI270773.1", "chr2_KI270773v1_alt");
+        m.insert("KI270774.1", "chr2_KI270774v1_alt");
+        m.insert("KI270769.1", "chr2_KI270769v1_alt");
+        m.insert("GL383521.1", "chr2_GL383521v1_alt");
+        m.insert("KI270772.1", "chr2_KI270772v1_alt");
+        m.insert("KI270775.1", "chr2_KI270775v1_alt");
+        m.insert("KI270771.1", "chr2_KI270771v1_alt");
+        m.insert("KI270768.1", "chr2_KI270768v1_alt");
+        m.insert("GL582966.2", "chr2_GL582966v2_alt");
+        m.insert("GL383522.1", "chr2_GL383522v1_alt");
+        m.insert("KI270776.1", "chr2_KI270776v1_alt");
+        m.insert("KI270767.1", "chr2_KI270767v1_alt");
+        m.insert("JH636055.2", "chr3_JH636055v2_alt");
+        m.insert("KI270783.1", "chr3_KI270783v1_alt");
+        m.insert("KI270780.1", "chr3_KI270780v1_alt");
+        m.insert("GL383526.1", "chr3_GL383526v1_alt");
+        m.insert("KI270777.1", "chr3_KI270777v1_alt");
+        m.insert("KI270778.1", "chr3_KI270778v1_alt");
+        m.insert("KI270781.1", "chr3_KI270781v1_alt");
+        m.insert("KI270779.1", "chr3_KI270779v1_alt");
+        m.insert("KI270782.1", "chr3_KI270782v1_alt");
+        m.insert("KI270784.1", "chr3_KI270784v1_alt");
+        m.insert("KI270790.1", "chr4_KI270790v1_alt");
+        m.insert("GL383528.1", "chr4_GL383528v1_alt");
+        m.insert("KI270787.1", "chr4_KI270787v1_alt");
+        m.insert("GL000257.2", "chr4_GL000257v2_alt");
+        m.insert("KI270788.1", "chr4_KI270788v1_alt");
+        m.insert("GL383527.1", "chr4_GL383527v1_alt");
+        m.insert("KI270785.1", "chr4_KI270785v1_alt");
+        m.insert("KI270789.1", "chr4_KI270789v1_alt");
+        m.insert("KI270786.1", "chr4_KI270786v1_alt");
+        m.insert("KI270793.1", "chr5_KI270793v1_alt");
+        m.insert("KI270792.1", "chr5_KI270792v1_alt");
+        m.insert("KI270791.1", "chr5_KI270791v1_alt");
+        m.insert("GL383532.1", "chr5_GL383532v1_alt");
+        m.insert("GL949742.1", "chr5_GL949742v1_alt");
+        m.insert("KI270794.1", "chr5_KI270794v1_alt");
+        m.insert("GL339449.2", "chr5_GL339449v2_alt");
+        m.insert("GL383530.1", "chr5_GL383530v1_alt");
+        m.insert("KI270796.1", "chr5_KI270796v1_alt");
+        m.insert("GL383531.1", "chr5_GL383531v1_alt");
+        m.insert("KI270795.1", "chr5_KI270795v1_alt");
+        m.insert("GL000250.2", "chr6_GL000250v2_alt");
+        m.insert("KI270800.1", "chr6_KI270800v1_alt");
+        m.insert("KI270799.1", "chr6_KI270799v1_alt");
+        m.insert("GL383533.1", "chr6_GL383533v1_alt");
+        m.insert("KI270801.1", "chr6_KI270801v1_alt");
+        m.insert("KI270802.1", "chr6_KI270802v1_alt");
+        m.insert("KB021644.2", "chr6_KB021644v2_alt");
+        m.insert("KI270797.1", "chr6_KI270797v1_alt");
+        m.insert("KI270798.1", "chr6_KI270798v1_alt");
+        m.insert("KI270804.1", "chr7_KI270804v1_alt");
+        m.insert("KI270809.1", "chr7_KI270809v1_alt");
+        m.insert("KI270806.1", "chr7_KI270806v1_alt");
+        m.insert("GL383534.2", "chr7_GL383534v2_alt");
+        m.insert("KI270803.1", "chr7_KI270803v1_alt");
+        m.insert("KI270808.1", "chr7_KI270808v1_alt");
+        m.insert("KI270807.1", "chr7_KI270807v1_alt");
+        m.insert("KI270805.1", "chr7_KI270805v1_alt");
+        m.insert("KI270818.1", "chr8_KI270818v1_alt");
+        m.insert("KI270812.1", "chr8_KI270812v1_alt");
+        m.insert("KI270811.1", "chr8_KI270811v1_alt");
+        m.insert("KI270821.1", "chr8_KI270821v1_alt");
+        m.insert("KI270813.1", "chr8_KI270813v1_alt");
+        m.insert("KI270822.1", "chr8_KI270822v1_alt");
+        m.insert("KI270814.1", "chr8_KI270814v1_alt");
+        m.insert("KI270810.1", "chr8_KI270810v1_alt");
+        m.insert("KI270819.1", "chr8_KI270819v1_alt");
+        m.insert("KI270820.1", "chr8_KI270820v1_alt");
+        m.insert("KI270817.1", "chr8_KI270817v1_alt");
+        m.insert("KI270816.1", "chr8_KI270816v1_alt");
+        m.insert("KI270815.1", "chr8_KI270815v1_alt");
+        m.insert("GL383539.1", "chr9_GL383539v1_alt");
+        m.insert("GL383540.1", "chr9_GL383540v1_alt");
+        m.insert("GL383541.1", "chr9_GL383541v1_alt");
+        m.insert("GL383542.1", "chr9_GL383542v1_alt");
+        m.insert("KI270823.1", "chr9_KI270823v1_alt");
+        m.insert("GL383545.1", "chr10_GL383545v1_alt");
+        m.insert("KI270824.1", "chr10_KI270824v1_alt");
+        m.insert("GL383546.1", "chr10_GL383546v1_alt");
+        m.insert("KI270825.1", "chr10_KI270825v1_alt");
+        m.insert("KI270832.1", "chr11_KI270832v1_alt");
+        m.insert("KI270830.1", "chr11_KI270830v1_alt");
+        m.insert("KI270831.1", "chr11_KI270831v1_alt");
+        m.insert("KI270829.1", "chr11_KI270829v1_alt");
+        m.insert("GL383547.1", "chr11_GL383547v1_alt");
+        m.insert("JH159136.1", "chr11_JH159136v1_alt");
+        m.insert("JH159137.1", "chr11_JH159137v1_alt");
+        m.insert("KI270827.1", "chr11_KI270827v1_alt");
+        m.insert("KI270826.1", "chr11_KI270826v1_alt");
+        m.insert("GL877875.1", "chr12_GL877875v1_alt");
+        m.insert("GL877876.1", "chr12_GL877876v1_alt");
+        m.insert("KI270837.1", "chr12_KI270837v1_alt");
+        m.insert("GL383549.1", "chr12_GL383549v1_alt");
+        m.insert("KI270835.1", "chr12_KI270835v1_alt");
+        m.insert("GL383550.2", "chr12_GL383550v2_alt");
+        m.insert("GL383552.1", "chr12_GL383552v1_alt");
+        m.insert("GL383553.2", "chr12_GL383553v2_alt");
+        m.insert("KI270834.1", "chr12_KI270834v1_alt");
+        m.insert("GL383551.1", "chr12_GL383551v1_alt");
+        m.insert("KI270833.1", "chr12_KI270833v1_alt");
+        m.insert("KI270836.1", "chr12_KI270836v1_alt");
+        m.insert("KI270840.1", "chr13_KI270840v1_alt");
+        m.insert("KI270839.1", "chr13_KI270839v1_alt");
+        m.insert("KI270843.1", "chr13_KI270843v1_alt");
+        m.insert("KI270841.1", "chr13_KI270841v1_alt");
+        m.insert("KI270838.1", "chr13_KI270838v1_alt");
+        m.insert("KI270842.1", "chr13_KI270842v1_alt");
+        m.insert("KI270844.1", "chr14_KI270844v1_alt");
+        m.insert("KI270847.1", "chr14_KI270847v1_alt");
+        m.insert("KI270845.1", "chr14_KI270845v1_alt");
+        m.insert("KI270846.1", "chr14_KI270846v1_alt");
+        m.insert("KI270852.1", "chr15_KI270852v1_alt");
+        m.insert("KI270851.1", "chr15_KI270851v1_alt");
+        m.insert("KI270848.1", "chr15_KI270848v1_alt");
+        m.insert("GL383554.1", "chr15_GL383554v1_alt");
+        m.insert("KI270849.1", "chr15_KI270849v1_alt");
+        m.insert("GL383555.2", "chr15_GL383555v2_alt");
+        m.insert("KI270850.1", "chr15_KI270850v1_alt");
+        m.insert("KI270854.1", "chr16_KI270854v1_alt");
+        m.insert("KI270856.1", "chr16_KI270856v1_alt");
+        m.insert("KI270855.1", "chr16_KI270855v1_alt");
+        m.insert("KI270853.1", "chr16_KI270853v1_alt");
+        m.insert("GL383556.1", "chr16_GL383556v1_alt");
+        m.insert("GL383557.1", "chr16_GL383557v1_alt");
+        m.insert("GL383563.3", "chr17_GL383563v3_alt");
+        m.insert("KI270862.1", "chr17_KI270862v1_alt");
+        m.insert("KI270861.1", "chr17_KI270861v1_alt");
+        m.insert("KI270857.1", "chr17_KI270857v1_alt");
+        m.insert("JH159146.1", "chr17_JH159146v1_alt");
+        m.insert("JH159147.1", "chr17_JH159147v1_alt");
+        m.insert("GL383564.2", "chr17_GL383564v2_alt");
+        m.insert("GL000258.2", "chr17_GL000258v2_alt");
+        m.insert("GL383565.1", "chr17_GL383565v1_alt");
+        m.insert("KI270858.1", "chr17_KI270858v1_alt");
+        m.insert("KI270859.1", "chr17_KI270859v1_alt");
+        m.insert("GL383566.1", "chr17_GL383566v1_alt");
+        m.insert("KI270860.1", "chr17_KI270860v1_alt");
+        m.insert("KI270864.1", "chr18_KI270864v1_alt");
+        m.insert("GL383567.1", "chr18_GL383567v1_alt");
+        m.insert("GL383570.1", "chr18_GL383570v1_alt");
+        m.insert("GL383571.1", "chr18_GL383571v1_alt");
+        m.insert("GL383568.1", "chr18_GL383568v1_alt");
+        m.insert("GL383569.1", "chr18_GL383569v1_alt");
+        m.insert("GL383572.1", "chr18_GL383572v1_alt");
+        m.insert("KI270863.1", "chr18_KI270863v1_alt");
+        m.insert("KI270868.1", "chr19_KI270868v1_alt");
+        m.insert("KI270865.1", "chr19_KI270865v1_alt");
+        m.insert("GL383573.1", "chr19_GL383573v1_alt");
+        m.insert("GL383575.2", "chr19_GL383575v2_alt");
+        m.insert("GL383576.1", "chr19_GL383576v1_alt");
+        m.insert("GL383574.1", "chr19_GL383574v1_alt");
+        m.insert("KI270866.1", "chr19_KI270866v1_alt");
+        m.insert("KI270867.1", "chr19_KI270867v1_alt");
+        m.insert("GL949746.1", "chr19_GL949746v1_alt");
+        m.insert("GL383577.2", "chr20_GL383577v2_alt");
+        m.insert("KI270869.1", "chr20_KI270869v1_alt");
+        m.insert("KI270871.1", "chr20_KI270871v1_alt");
+        m.insert("KI270870.1", "chr20_KI270870v1_alt");
+        m.insert("GL383578.2", "chr21_GL383578v2_alt");
+        m.insert("KI270874.1", "chr21_KI270874v1_alt");
+        m.insert("KI270873.1", "chr21_KI270873v1_alt");
+        m.insert("GL383579.2", "chr21_GL383579v2_alt");
+        m.insert("GL383580.2", "chr21_GL383580v2_alt");
+        m.insert("GL383581.2", "chr21_GL383581v2_alt");
+        m.insert("KI270872.1", "chr21_KI270872v1_alt");
+        m.insert("KI270875.1", "chr22_KI270875v1_alt");
+        m.insert("KI270878.1", "chr22_KI270878v1_alt");
+        m.insert("KI270879.1", "chr22_KI270879v1_alt");
+        m.insert("KI270876.1", "chr22_KI270876v1_alt");
+        m.insert("KI270877.1", "chr22_KI270877v1_alt");
+        m.insert("GL383583.2", "chr22_GL383583v2_alt");
+        m.insert("GL383582.2", "chr22_GL383582v2_alt");
+        m.insert("KI270880.1", "chrX_KI270880v1_alt");
+        m.insert("KI270881.1", "chrX_KI270881v1_alt");
+        m.insert("KI270882.1", "chr19_KI270882v1_alt");
+        m.insert("KI270883.1", "chr19_KI270883v1_alt");
+        m.insert("KI270884.1", "chr19_KI270884v1_alt");
+        m.insert("KI270885.1", "chr19_KI270885v1_alt");
+        m.insert("KI270886.1", "chr19_KI270886v1_alt");
+        m.insert("KI270887.1", "chr19_KI270887v1_alt");
+        m.insert("KI270888.1", "chr19_KI270888v1_alt");
+        m.insert("KI270889.1", "chr19_KI270889v1_alt");
+        m.insert("KI270890.1", "chr19_KI270890v1_alt");
+        m.insert("KI270891.1", "chr19_KI270891v1_alt");
+        m.insert("KI270892.1", "chr1_KI270892v1_alt");
+        m.insert("KI270894.1", "chr2_KI270894v1_alt");
+        m.insert("KI270893.1", "chr2_KI270893v1_alt");
+        m.insert("KI270895.1", "chr3_KI270895v1_alt");
+        m.insert("KI270896.1", "chr4_KI270896v1_alt");
+        m.insert("KI270897.1", "chr5_KI270897v1_alt");
+        m.insert("KI270898.1", "chr5_KI270898v1_alt");
+        m.insert("GL000251.2", "chr6_GL000251v2_alt");
+        m.insert("KI270899.1", "chr7_KI270899v1_alt");
+        m.insert("KI270901.1", "chr8_KI270901v1_alt");
+        m.insert("KI270900.1", "chr8_KI270900v1_alt");
+        m.insert("KI270902.1", "chr11_KI270902v1_alt");
+        m.insert("KI270903.1", "chr11_KI270903v1_alt");
+        m.insert("KI270904.1", "chr12_KI270904v1_alt");
+        m.insert("KI270906.1", "chr15_KI270906v1_alt");
+        m.insert("KI270905.1", "chr15_KI270905v1_alt");
+        m.insert("KI270907.1", "chr17_KI270907v1_alt");
+        m.insert("KI270910.1", "chr17_KI270910v1_alt");
+        m.insert("KI270909.1", "chr17_KI270909v1_alt");
+        m.insert("JH159148.1", "chr17_JH159148v1_alt");
+        m.insert("KI270908.1", "chr17_KI270908v1_alt");
+        m.insert("KI270912.1", "chr18_KI270912v1_alt");
+        m.insert("KI270911.1", "chr18_KI270911v1_alt");
+        m.insert("GL949747.2", "chr19_GL949747v2_alt");
+        m.insert("KB663609.1", "chr22_KB663609v1_alt");
+        m.insert("KI270913.1", "chrX_KI270913v1_alt");
+        m.insert("KI270914.1", "chr19_KI270914v1_alt");
+        m.insert("KI270915.1", "chr19_KI270915v1_alt");
+        m.insert("KI270916.1", "chr19_KI270916v1_alt");
+        m.insert("KI270917.1", "chr19_KI270917v1_alt");
+        m.insert("KI270918.1", "chr19_KI270918v1_alt");
+        m.insert("KI270919.1", "chr19_KI270919v1_alt");
+        m.insert("KI270920.1", "chr19_KI270920v1_alt");
+        m.insert("KI270921.1", "chr19_KI270921v1_alt");
+        m.insert("KI270922.1", "chr19_KI270922v1_alt");
+        m.insert("KI270923.1", "chr19_KI270923v1_alt");
+        m.insert("KI270924.1", "chr3_KI270924v1_alt");
+        m.insert("KI270925.1", "chr4_KI270925v1_alt");
+        m.insert("GL000252.2", "chr6_GL000252v2_alt");
+        m.insert("KI270926.1", "chr8_KI270926v1_alt");
+        m.insert("KI270927.1", "chr11_KI270927v1_alt");
+        m.insert("GL949748.2", "chr19_GL949748v2_alt");
+        m.insert("KI270928.1", "chr22_KI270928v1_alt");
+        m.insert("KI270929.1", "chr19_KI270929v1_alt");
+        m.insert("KI270930.1", "chr19_KI270930v1_alt");
+        m.insert("KI270931.1", "chr19_KI270931v1_alt");
+        m.insert("KI270932.1", "chr19_KI270932v1_alt");
+        m.insert("KI270933.1", "chr19_KI270933v1_alt");
+        m.insert("GL000209.2", "chr19_GL000209v2_alt");
+        m.insert("KI270934.1", "chr3_KI270934v1_alt");
+        m.insert("GL000253.2", "chr6_GL000253v2_alt");
+        m.insert("GL949749.2", "chr19_GL949749v2_alt");
+        m.insert("KI270935.1", "chr3_KI270935v1_alt");
+        m.insert("GL000254.2", "chr6_GL000254v2_alt");
+        m.insert("GL949750.2", "chr19_GL949750v2_alt");
+        m.insert("KI270936.1", "chr3_KI270936v1_alt");
+        m.insert("GL000255.2", "chr6_GL000255v2_alt");
+        m.insert("GL949751.2", "chr19_GL949751v2_alt");
+        m.insert("KI270937.1", "chr3_KI270937v1_alt");
+        m.insert("GL000256.2", "chr6_GL000256v2_alt");
+        m.insert("GL949752.1", "chr19_GL949752v1_alt");
+        m.insert("KI270758.1", "chr6_KI270758v1_alt");
+        m.insert("GL949753.2", "chr19_GL949753v2_alt");
+        m.insert("KI270938.1", "chr19_KI270938v1_alt");
+        m.insert("KI270302.1", "chrUn_KI270302v1");
+        m.insert("KI270304.1", "chrUn_KI270304v1");
+        m.insert("KI270303.1", "chrUn_KI270303v1");
+        m.insert("KI270305.1", "chrUn_KI270305v1");
+        m.insert("KI270322.1", "chrUn_KI270322v1");
+        m.insert("KI270320.1", "chrUn_KI270320v1");
+        m.insert("KI270310.1", "chrUn_KI270310v1");
+        m.insert("KI270316.1", "chrUn_KI270316v1");
+        m.insert("KI270315.1", "chrUn_KI270315v1");
+        m.insert("KI270312.1", "chrUn_KI270312v1");
+        m.insert("KI270311.1", "chrUn_KI270311v1");
+        m.insert("KI270317.1", "chrUn_KI270317v1");
+        m.insert("KI270412.1", "chrUn_KI270412v1");
+        m.insert("KI270411.1", "chrUn_KI270411v1");
+        m.insert("KI270414.1", "chrUn_KI270414v1");
+        m.insert("KI270419.1", "chrUn_KI270419v1");
+        m.insert("KI270418.1", "chrUn_KI270418v1");
+        m.insert("KI270420.1", "chrUn_KI270420v1");
+        m.insert("KI270424.1", "chrUn_KI270424v1");
+        m.insert("KI270417.1", "chrUn_KI270417v1");
+        m.insert("KI270422.1", "chrUn_KI270422v1");
+        m.insert("KI270423.1", "chrUn_KI270423v1");
+        m.insert("KI270425.1", "chrUn_KI270425v1");
+        m.insert("KI270429.1", "chrUn_KI270429v1");
+        m.insert("KI270442.1", "chrUn_KI270442v1");
+        m.insert("KI270466.1", "chrUn_KI270466v1");
+        m.insert("KI270465.1", "chrUn_KI270465v1");
+        m.insert("KI270467.1", "chrUn_KI270467v1");
+        m.insert("KI270435.1", "chrUn_KI270435v1");
+        m.insert("KI270438.1", "chrUn_KI270438v1");
+        m.insert("KI270468.1", "chrUn_KI270468v1");
+        m.insert("KI270510.1", "chrUn_KI270510v1");
+        m.insert("KI270509.1", "chrUn_KI270509v1");
+        m.insert("KI270518.1", "chrUn_KI270518v1");
+        m.insert("KI270508.1", "chrUn_KI270508v1");
+        m.insert("KI270516.1", "chrUn_KI270516v1");
+        m.insert("KI270512.1", "chrUn_KI270512v1");
+        m.insert("KI270519.1", "chrUn_KI270519v1");
+        m.insert("KI270522.1", "chrUn_KI270522v1");
+        m.insert("KI270511.1", "chrUn_KI270511v1");
+        m.insert("KI270515.1", "chrUn_KI270515v1");
+        m.insert("KI270507.1", "chrUn_KI270507v1");
+        m.insert("KI270517.1", "chrUn_KI270517v1");
+        m.insert("KI270529.1", "chrUn_KI270529v1");
+        m.insert("KI270528.1", "chrUn_KI270528v1");
+        m.insert("KI270530.1", "chrUn_KI270530v1");
+        m.insert("KI270539.1", "chrUn_KI270539v1");
+        m.insert("KI270538.1", "chrUn_KI270538v1");
+        m.insert("KI270544.1", "chrUn_KI270544v1");
+        m.insert("KI270548.1", "chrUn_KI270548v1");
+        m.insert("KI270583.1", "chrUn_KI270583v1");
+        m.insert("KI270587.1", "chrUn_KI270587v1");
+        m.insert("KI270580.1", "chrUn_KI270580v1");
+        m.insert("KI270581.1", "chrUn_KI270581v1");
+        m.insert("KI270579.1", "chrUn_KI270579v1");
+        m.insert("KI270589.1", "chrUn_KI270589v1");
+        m.insert("KI270590.1", "chrUn_KI270590v1");
+        m.insert("KI270584.1", "chrUn_KI270584v1");
+        m.insert("KI270582.1", "chrUn_KI270582v1");
+        m.insert("KI270588.1", "chrUn_KI270588v1");
+        m.insert("KI270593.1", "chrUn_KI270593v1");
+        m.insert("KI270591.1", "chrUn_KI270591v1");
+        m.insert("KI270330.1", "chrUn_KI270330v1");
+        m.insert("KI270329.1", "chrUn_KI270329v1");
+        m.insert("KI270334.1", "chrUn_KI270334v1");
+        m.insert("KI270333.1", "chrUn_KI270333v1");
+        m.insert("KI270335.1", "chrUn_KI270335v1");
+        m.insert("KI270338.1", "chrUn_KI270338v1");
+        m.insert("KI270340.1", "chrUn_KI270340v1");
+        m.insert("KI270336.1", "chrUn_KI270336v1");
+        m.insert("KI270337.1", "chrUn_KI270337v1");
+        m.insert("KI270363.1", "chrUn_KI270363v1");
+        m.insert("KI270364.1", "chrUn_KI270364v1");
+        m.insert("KI270362.1", "chrUn_KI270362v1");
+        m.insert("KI270366.1", "chrUn_KI270366v1");
+        m.insert("KI270378.1", "chrUn_KI270378v1");
+        m.insert("KI270379.1", "chrUn_KI270379v1");
+        m.insert("KI270389.1", "chrUn_KI270389v1");
+        m.insert("KI270390.1", "chrUn_KI270390v1");
+        m.insert("KI270387.1", "chrUn_KI270387v1");
+        m.insert("KI270395.1", "chrUn_KI270395v1");
+        m.insert("KI270396.1", "chrUn_KI270396v1");
+        m.insert("KI270388.1", "chrUn_KI270388v1");
+        m.insert("KI270394.1", "chrUn_KI270394v1");
+        m.insert("KI270386.1", "chrUn_KI270386v1");
+        m.insert("KI270391.1", "chrUn_KI270391v1");
+        m.insert("KI270383.1", "chrUn_KI270383v1");
+        m.insert("KI270393.1", "chrUn_KI270393v1");
+        m.insert("KI270384.1", "chrUn_KI270384v1");
+        m.insert("KI270392.1", "chrUn_KI270392v1");
+        m.insert("KI270381.1", "chrUn_KI270381v1");
+        m.insert("KI270385.1", "chrUn_KI270385v1");
+        m.insert("KI270382.1", "chrUn_KI270382v1");
+        m.insert("KI270376.1", "chrUn_KI270376v1");
+        m.insert("KI270374.1", "chrUn_KI270374v1");
+        m.insert("KI270372.1", "chrUn_KI270372v1");
+        m.insert("KI270373.1", "chrUn_KI270373v1");
+        m.insert("KI270375.1", "chrUn_KI270375v1");
+        m.insert("KI270371.1", "chrUn_KI270371v1");
+        m.insert("KI270448.1", "chrUn_KI270448v1");
+        m.insert("KI270521.1", "chrUn_KI270521v1");
+        m.insert("GL000195.1", "chrUn_GL000195v1");
+        m.insert("GL000219.1", "chrUn_GL000219v1");
+        m.insert("GL000220.1", "chrUn_GL000220v1");
+        m.insert("GL000224.1", "chrUn_GL000224v1");
+        m.insert("KI270741.1", "chrUn_KI270741v1");
+        m.insert("GL000226.1", "chrUn_GL000226v1");
+        m.insert("GL000213.1", "chrUn_GL000213v1");
+        m.insert("KI270743.1", "chrUn_KI270743v1");
+        m.insert("KI270744.1", "chrUn_KI270744v1");
+        m.insert("KI270745.1", "chrUn_KI270745v1");
+        m.insert("KI270746.1", "chrUn_KI270746v1");
+        m.insert("KI270747.1", "chrUn_KI270747v1");
+        m.insert("KI270748.1", "chrUn_KI270748v1");
+        m.insert("KI270749.1", "chrUn_KI270749v1");
+        m.insert("KI270750.1", "chrUn_KI270750v1");
+        m.insert("KI270751.1", "chrUn_KI270751v1");
+        m.insert("KI270752.1", "chrUn_KI270752v1");
+        m.insert("KI270753.1", "chrUn_KI270753v1");
+        m.insert("KI270754.1", "chrUn_KI270754v1");
+        m.insert("KI270755.1", "chrUn_KI270755v1");
+        m.insert("KI270756.1", "chrUn_KI270756v1");
+        m.insert("KI270757.1", "chrUn_KI270757v1");
+        m.insert("GL000214.1", "chrUn_GL000214v1");
+        m.insert("KI270742.1", "chrUn_KI270742v1");
+        m.insert("GL000216.2", "chrUn_GL000216v2");
+        m.insert("GL000218.1", "chrUn_GL000218v1");
+        m.insert("KI270740.1", "chrY_KI270740v1_random");
+        m
+    };
+}
+
+/// Returns true if the reference sequences given as (name, length) are
+/// GRCh38: a nuclear primary sequence is present in any naming style, and all
+/// primary sequences present have their GRCh38 lengths.
+pub fn is_grch38(sequences: &[(String, usize)]) -> bool {
+    let mut has_nuclear = false;
+    for ((ucsc, ensembl, genbank, refseq), length) in PRIMARY_CONTIGS.iter().zip(PRIMARY_LENGTHS) {
+        for (name, v) in sequences.iter() {
+            if name == ucsc || name == ensembl || name == genbank || name == refseq {
+                if *v != length {
+                    return false;
+                }
+                has_nuclear |= length != RCRS_LENGTH;
+            }
+        }
+    }
+    has_nuclear
+}
+
+/// Returns the UCSC name of a GRCh38 sequence given in any naming style.
+pub fn get_ucsc_name(name: &str) -> Option<String> {
+    for (ucsc, ensembl, genbank, refseq) in PRIMARY_CONTIGS.iter() {
+        if name == *ucsc || name == *ensembl || name == *genbank || name == *refseq {
+            return Some(ucsc.to_string());
+        }
+    }
+    if let Some(v) = UCSC_NAMES.get(name) {
+        return Some(v.to_string());
+    }
+    if UCSC_NAMES.values().any(|v| *v == name) {
+        return Some(name.to_string());
+    }
+    None
+}
+
+/// Returns the accession of a UCSC alt, fix or random sequence name, for
+/// example `GL000009.2` for `chr14_GL000009v2_random`.
+fn get_accession_of_ucsc_name(name: &str) -> Option<String> {
+    let accession = name.split('_').nth(1)?;
+    let (id, version) = accession.rsplit_once('v')?;
+    Some(format!("{}.{}", id, version))
+}
+
+/// Returns the name of a GRCh38 sequence in the given naming style. `None` is
+/// returned if the sequence is unknown or has no name in that style, as is the
+/// case for RefSeq names of alt and random sequences.
+pub fn get_contig_name(name: &str, naming: ContigNaming) -> Option<String> {
+    let ucsc_name = get_ucsc_name(name)?;
+    for (ucsc, ensembl, genbank, refseq) in PRIMARY_CONTIGS.iter() {
+        if ucsc_name == *ucsc {
+            let v = match naming {
+                ContigNaming::Ucsc => ucsc,
+                ContigNaming::Ensembl => ensembl,
+                ContigNaming::GenBank => genbank,
+                ContigNaming::RefSeq => refseq,
+            };
+            return Some(v.to_string());
+        }
+    }
+    match naming {
+        ContigNaming::Ucsc => Some(ucsc_name),
+        ContigNaming::Ensembl | ContigNaming::GenBank => get_accession_of_ucsc_name(&ucsc_name),
+        ContigNaming::RefSeq => None,
+    }
+}
+
+/// Length of the revised Cambridge Reference Sequence (rCRS), the human
+/// mitochondrion of GRCh38 and of hg19 `MT` in GRCh37.
+pub const RCRS_LENGTH: usize = 16569;
+/// Length of the Yoruba mitochondrial sequence used as `chrM` in hg19.
+pub const YORUBA_MITO_LENGTH: usize = 16571;
+
+/// Names of a mitochondrion which do not identify its sequence.
+static MITO_NAMES: [&str; 7] = ["chrM", "chrMT", "M", "MT", "Mito", "mito", "mitochondrion"];
+/// Accessions of the rCRS.
+static RCRS_ACCESSIONS: [&str; 2] = ["NC_012920.1", "J01415.2"];
+/// Accessions of the Yoruba mitochondrial sequence.
+static YORUBA_MITO_ACCESSIONS: [&str; 1] = ["NC_001807.4"];
+
+/// Human mitochondrial reference sequences. Positions on the two differ, so
+/// SPDIs on one are not comparable with SPDIs on the other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MitoSequence {
+    /// Revised Cambridge Reference Sequence (NC_012920.1)
+    Rcrs,
+    /// Yoruba sequence used as hg19 `chrM` (NC_001807.4)
+    Yoruba,
+    /// Neither, or could not be told from the name and length.
+    Unknown,
+}
+
+impl MitoSequence {
+    /// Tells the mitochondrial sequence of a sequence name and length. Returns
+    /// `None` if the name is not a mitochondrial name.
+    pub fn of_sequence(name: &str, length: Option<usize>) -> Option<MitoSequence> {
+        if RCRS_ACCESSIONS.contains(&name) {
+            return Some(MitoSequence::Rcrs);
+        }
+        if YORUBA_MITO_ACCESSIONS.contains(&name) {
+            return Some(MitoSequence::Yoruba);
+        }
+        if !MITO_NAMES.contains(&name) {
+            return None;
+        }
+        match length {
+            Some(RCRS_LENGTH) => Some(MitoSequence::Rcrs),
+            Some(YORUBA_MITO_LENGTH) => Some(MitoSequence::Yoruba),
+            _ => Some(MitoSequence::Unknown),
+        }
+    }
+}
+
+/// Resolves contig names of input files to sequence names in a reference.
+///
+/// A name is looked up as is, in the alias table, as a mitochondrial name,
+/// with a `chr` prefix added or removed, and finally, if the reference is
+/// GRCh38, through the GRCh38 names in all naming styles. Only names present in
+/// the reference are returned, so any species works as long as its names or
+/// aliases match the reference.
+#[derive(Clone, Debug, Default)]
+pub struct ContigResolver {
+    names: HashSet<String>,
+    aliases: HashMap<String, String>,
+    mito: Option<(String, MitoSequence)>,
+    grch38: bool,
+}
+
+impl ContigResolver {
+    /// Creates a resolver for reference sequences given as (name, length).
+    pub fn new(sequences: &[(String, usize)]) -> ContigResolver {
+        let mito = sequences.iter().find_map(|(name, length)| {
+            MitoSequence::of_sequence(name, Some(*length)).map(|v| (name.clone(), v))
+        });
+        ContigResolver {
+            names: sequences.iter().map(|(name, _)| name.clone()).collect(),
+            aliases: HashMap::default(),
+            mito,
+            grch38: is_grch38(sequences),
+        }
+    }
+
+    /// Whether the reference is GRCh38, in which case names of its sequences
+    /// in other naming styles are known.
+    pub fn is_grch38(&self) -> bool {
+        self.grch38
+    }
+
+    /// Name and kind of the mitochondrial sequence in the reference.
+    pub fn mito(&self) -> Option<(&str, MitoSequence)> {
+        self.mito.as_ref().map(|(name, v)| (name.as_str(), *v))
+    }
+
+    /// Adds an alias of a reference sequence. Aliases of sequences which are
+    /// not in the reference are ignored.
+    pub fn add_alias(&mut self, alias: &str, name: &str) {
+        if self.names.contains(name) && alias != name {
+            self.aliases.insert(alias.to_string(), name.to_string());
+        }
+    }
+
+    /// Reads an alias file such as UCSC `chromAlias.txt`. Each line lists names
+    /// of one sequence separated by tabs or spaces, and whichever of them is in
+    /// the reference becomes the target of the others. Lines starting with `#`
+    /// are skipped. Returns the number of aliases added.
+    pub fn read_aliases(&mut self, path: &Path) -> std::io::Result<usize> {
+        let reader = std::io::BufReader::new(std::fs::File::open(path)?);
+        let mut count: usize = 0;
+        for line in reader.lines() {
+            let line = line?;
+            if line.starts_with('#') {
+                continue;
+            }
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let name = match words.iter().find(|v| self.names.contains(**v)) {
+                Some(v) => v.to_string(),
+                None => continue,
+            };
+            for alias in words.iter() {
+                if *alias != name {
+                    self.add_alias(alias, &name);
+                    count += 1;
+                }
+            }
+        }
+        Ok(count)
+    }
+
+    /// Returns the reference sequence name of `name`, or `None` if it cannot
+    /// be resolved.
+    pub fn resolve(&self, name: &str) -> Option<String> {
+        if self.names.contains(name) {
+            return Some(name.to_string());
+        }
+        if let Some(v) = self.aliases.get(name) {
+            return Some(v.clone());
+        }
+        if let Some(input_mito) = MitoSequence::of_sequence(name, None) {
+            let (mito_name, ref_mito) = self.mito.as_ref()?;
+            return match (input_mito, ref_mito) {
+                (MitoSequence::Rcrs, MitoSequence::Yoruba)
+                | (MitoSequence::Yoruba, MitoSequence::Rcrs) => None,
+                _ => Some(mito_name.clone()),
+            };
+        }
+        let prefixed = match name.strip_prefix("chr") {
+            Some(v) => v.to_string(),
+            None => format!("chr{}", name),
+        };
+        if self.names.contains(&prefixed) {
+            return Some(prefixed);
+        }
+        if !self.grch38 {
+            return None;
+        }
+        for naming in [
+            ContigNaming::Ucsc,
+            ContigNaming::Ensembl,
+            ContigNaming::GenBank,
+            ContigNaming::RefSeq,
+        ] {
+            if let Some(v) = get_contig_name(name, naming) {
+                if self.names.contains(&v) {
+                    return Some(v);
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests_contig {
+    use super::*;
+
+    #[test]
+    fn test_get_contig_name() {
+        assert_eq!(Some("NC_000022.11".to_string()), get_contig_name("chr22", ContigNaming::RefSeq));
+        assert_eq!(Some("chr22".to_string()), get_contig_name("NC_000022.11", ContigNaming::Ucsc));
+        assert_eq!(Some("MT".to_string()), get_contig_name("chrM", ContigNaming::Ensembl));
+        assert_eq!(
+            Some("GL000009.2".to_string()),
+            get_contig_name("chr14_GL000009v2_random", ContigNaming::GenBank)
+        );
+        assert_eq!(
+            Some("chr14_GL000009v2_random".to_string()),
+            get_contig_name("GL000009.2", ContigNaming::Ucsc)
+        );
+        assert_eq!(None, get_contig_name("chr14_GL000009v2_random", ContigNaming::RefSeq));
+        assert_eq!(None, get_contig_name("scaffold_1", ContigNaming::Ucsc));
+    }
+
+    #[test]
+    fn test_is_grch38() {
+        assert!(is_grch38(&[("chr1".to_string(), 248956422), ("MT".to_string(), RCRS_LENGTH)]));
+        assert!(is_grch38(&[("NC_000022.11".to_string(), 50818468), ("scaffold_1".to_string(), 1000)]));
+        // hg19 chr1, a small sequence named like a GRCh38 one, and a mitochondrion only
+        assert!(!is_grch38(&[("chr1".to_string(), 249250621)]));
+        assert!(!is_grch38(&[("chr1".to_string(), 70)]));
+        assert!(!is_grch38(&[("chrM".to_string(), RCRS_LENGTH)]));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let sequences: Vec<(String, usize)> = vec![
+            ("chr1".to_string(), 248956422),
+            ("chr19".to_string(), 58617616),
+            ("chrM".to_string(), RCRS_LENGTH),
+            ("chr14_GL000009v2_random".to_string(), 201709),
+            ("scaffold_1".to_string(), 1000),
+            ("2".to_string(), 242193529),
+        ];
+        let mut resolver = ContigResolver::new(&sequences);
+        assert!(resolver.is_grch38());
+        resolver.add_alias("contig_one", "scaffold_1");
+        assert_eq!(Some("chr19".to_string()), resolver.resolve("chr19"));
+        assert_eq!(Some("chr19".to_string()), resolver.resolve("19"));
+        assert_eq!(Some("2".to_string()), resolver.resolve("chr2"));
+        assert_eq!(Some("chrM".to_string()), resolver.resolve("MT"));
+        assert_eq!(Some("chr1".to_string()), resolver.resolve("NC_000001.11"));
+        assert_eq!(Some("chr14_GL000009v2_random".to_string()), resolver.resolve("GL000009.2"));
+        assert_eq!(Some("scaffold_1".to_string()), resolver.resolve("contig_one"));
+        assert_eq!(None, resolver.resolve("chr20"));
+        assert_eq!(None, resolver.resolve("MU273354.1"));
+    }
+
+    #[test]
+    fn test_resolve_not_grch38() {
+        // hg19 chr1, and a small sequence named like a GRCh38 one
+        for length in [249250621, 70] {
+            let resolver = ContigResolver::new(&[("chr1".to_string(), length)]);
+            assert!(!resolver.is_grch38());
+            assert_eq!(Some("chr1".to_string()), resolver.resolve("1"));
+            assert_eq!(None, resolver.resolve("NC_000001.11"));
+            assert_eq!(None, resolver.resolve("CM000663.2"));
+        }
+        let mito_only = ContigResolver::new(&[("chrM".to_string(), RCRS_LENGTH)]);
+        assert!(!mito_only.is_grch38());
+    }
+
+    #[test]
+    fn test_resolve_mito() {
+        let rcrs = ContigResolver::new(&[("MT".to_string(), RCRS_LENGTH)]);
+        assert_eq!(Some(("MT", MitoSequence::Rcrs)), rcrs.mito());
+        assert_eq!(Some("MT".to_string()), rcrs.resolve("chrM"));
+        assert_eq!(Some("MT".to_string()), rcrs.resolve("NC_012920.1"));
+        assert_eq!(None, rcrs.resolve("NC_001807.4"));
+        let yoruba = ContigResolver::new(&[("chrM".to_string(), YORUBA_MITO_LENGTH)]);
+        assert_eq!(Some(("chrM", MitoSequence::Yoruba)), yoruba.mito());
+        assert_eq!(Some("chrM".to_string()), yoruba.resolve("MT"));
+        assert_eq!(None, yoruba.resolve("NC_012920.1"));
+        let none = ContigResolver::new(&[("chr1".to_string(), 1000)]);
+        assert_eq!(None, none.resolve("chrM"));
+    }
+}
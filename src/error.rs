@@ -29,7 +29,50 @@ pub enum Error {
         ref_base: String,
         alt_base: String,
     },
+    UnknownSequence {
+        name: String,
+    },
+    OutOfBounds {
+        chrom: String,
+        pos: usize,
+        len: usize,
+    },
+    ReferenceMismatch {
+        chrom: String,
+        pos: usize,
+        ref_base: String,
+        reference: String,
+    },
+    /// An error while converting a variant, with the variant as given.
+    Conversion {
+        chrom: String,
+        pos: usize,
+        ref_base: String,
+        alt_base: String,
+        source: Box<Error>,
+    },
     TwoBitError(twobit::Error),
+    FastaError(String),
+}
+
+impl Error {
+    /// Name of the variant, for counting errors by kind. Conversion errors
+    /// have the kind of their source.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::InvalidBase { .. } => "InvalidBase",
+            Error::NoNonRepeat { .. } => "NoNonRepeat",
+            Error::NotIndel { .. } => "NotIndel",
+            Error::InvalidPosition { .. } => "InvalidPosition",
+            Error::EmptyVariant { .. } => "EmptyVariant",
+            Error::UnknownSequence { .. } => "UnknownSequence",
+            Error::OutOfBounds { .. } => "OutOfBounds",
+            Error::ReferenceMismatch { .. } => "ReferenceMismatch",
+            Error::Conversion { source, .. } => source.kind(),
+            Error::TwoBitError(_) => "TwoBitError",
+            Error::FastaError(_) => "FastaError",
+        }
+    }
 }
 
 impl std::fmt::Display for Error {
@@ -45,10 +88,31 @@ impl std::fmt::Display for Error {
             Error::NotIndel { chrom, pos, ref_base, alt_base } => write!(f, "Not an indel: {}:{}:{}:{}", chrom, pos, ref_base, alt_base),
             Error::InvalidPosition { chrom, pos } => write!(f, "Invalid genomic positions: {}:{}", chrom, pos),
             Error::EmptyVariant { chrom, pos, ref_base, alt_base } => write!(f, "Empty variant: {}:{}:{}:{}", chrom, pos, ref_base, alt_base),
+            Error::UnknownSequence { name } => write!(f, "Sequence not in the reference: {}", name),
+            Error::OutOfBounds { chrom, pos, len } => write!(f, "Out of sequence bounds: {}:{} with {} reference bases", chrom, pos, len),
+            Error::ReferenceMismatch { chrom, pos, ref_base, reference } => write!(f, "Reference bases do not match: {}:{}:{} but the reference has {}", chrom, pos, ref_base, reference),
+            Error::Conversion { chrom, pos, ref_base, alt_base, source } => write!(f, "{}. {}:{}:{}:{}", source, chrom, pos, ref_base, alt_base),
             Error::TwoBitError(e) => write!(f, "TwoBitError: {}", e),
+            Error::FastaError(e) => write!(f, "FastaError: {}", e),
         }
     }
 }
 
-impl std::error::Error for Error {}
+impl From<twobit::Error> for Error {
+    fn from(e: twobit::Error) -> Self {
+        match e {
+            twobit::Error::MissingName(name) => Error::UnknownSequence { name },
+            _ => Error::TwoBitError(e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Conversion { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
 
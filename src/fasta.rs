@@ -0,0 +1,51 @@
+// Copyright Ryangguk Kim @ Oak Bioinformatics, LLC
+//
+// This software is available under a dual licensing model, offering users the choice between the Affero General Public License version 3 (AGPL-3) for open-source use and a commercial license for proprietary or commercial use.
+//
+// To obtain a commercial license, please contact info@oakbioinformatics.com.
+
+use crate::error::Error;
+use std::io::{BufRead, Read};
+use std::path::Path;
+
+/// Returns true if the file starts with a FASTA header line rather than the
+/// 2bit signature.
+pub fn is_fasta(path: &Path) -> bool {
+    let mut buf = [0u8; 1];
+    match std::fs::File::open(path).and_then(|mut f| f.read_exact(&mut buf)) {
+        Ok(_) => buf[0] == b'>',
+        Err(_) => false,
+    }
+}
+
+/// Reads the sequences of an uncompressed FASTA file in file order, in upper
+/// case like sequences read from 2bit files. Sequence names are the first
+/// word of header lines.
+pub fn read_fasta(path: &Path) -> Result<Vec<(String, Vec<u8>)>, Error> {
+    let to_error = |e: std::io::Error| Error::FastaError(format!("{}: {}", path.display(), e));
+    let reader = std::io::BufReader::new(std::fs::File::open(path).map_err(to_error)?);
+    let mut sequences: Vec<(String, Vec<u8>)> = Vec::new();
+    for line in reader.lines() {
+        let line = line.map_err(to_error)?;
+        if let Some(header) = line.strip_prefix('>') {
+            let name = header.split_whitespace().next().unwrap_or("").to_string();
+            sequences.push((name, Vec::new()));
+            continue;
+        }
+        match sequences.last_mut() {
+            Some((_, seq)) => seq.extend(
+                line.bytes()
+                    .filter(|c| !c.is_ascii_whitespace())
+                    .map(|c| c.to_ascii_uppercase()),
+            ),
+            None if line.trim().is_empty() => {}
+            None => {
+                return Err(Error::FastaError(format!(
+                    "{}: sequence before the first header line",
+                    path.display()
+                )))
+            }
+        }
+    }
+    Ok(sequences)
+}
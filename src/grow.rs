@@ -5,45 +5,273 @@
 // To obtain a commercial license, please contact info@oakbioinformatics.com.
 
 use crate::error::Error;
-use crate::util::is_base_same_as_char;
-use crate::Base;
+use crate::fasta;
+use crate::util::{get_chrom_str, is_base_same_as_char};
+use crate::{Base, SpdiConversion};
+use ahash::{HashMap, HashSet};
+use std::io::Cursor;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
 static GROW_RIGHT_MAX: usize = 10000;
 
+/// 2bit reader over a copy of the file which is shared with other readers.
+type TwoBitReader = twobit::TwoBitFile<Cursor<Arc<[u8]>>>;
+
+/// 2bit readers over one in-memory copy of a 2bit file. A reader needs `&mut`
+/// access to seek, so each read takes a reader out of the pool and puts it
+/// back afterwards. Threads thus read in parallel with a reader each, and a new
+/// reader is only made when all readers are in use.
+struct TwoBitPool {
+    buf: Arc<[u8]>,
+    readers: Mutex<Vec<TwoBitReader>>,
+}
+
+impl TwoBitPool {
+    fn open(twobit_path: &PathBuf) -> Result<TwoBitPool, twobit::Error> {
+        let buf: Arc<[u8]> = std::fs::read(twobit_path)?.into();
+        let reader = twobit::TwoBitFile::from_buf(buf.clone())?;
+        Ok(TwoBitPool {
+            buf,
+            readers: Mutex::new(vec![reader]),
+        })
+    }
+
+    fn with_reader<T>(
+        &self,
+        f: impl FnOnce(&mut TwoBitReader) -> Result<T, twobit::Error>,
+    ) -> Result<T, twobit::Error> {
+        // A poisoned lock only means another thread panicked while taking or
+        // putting back a reader. The list of readers is still valid.
+        let reader = self.readers.lock().unwrap_or_else(|e| e.into_inner()).pop();
+        let mut reader = match reader {
+            Some(v) => v,
+            None => twobit::TwoBitFile::from_buf(self.buf.clone())?,
+        };
+        let ret = f(&mut reader);
+        self.readers.lock().unwrap_or_else(|e| e.into_inner()).push(reader);
+        ret
+    }
+}
+
+/// Sequences of a reference, from a 2bit or a FASTA file.
+enum Reference {
+    TwoBit(TwoBitPool),
+    Fasta(HashMap<String, Vec<u8>>),
+}
+
+/// Grows indels to their fully justified extent on the reference.
+///
+/// Cloning a `Grower` is cheap and all clones read the same in-memory
+/// reference.
+#[derive(Clone)]
 pub struct Grower {
-    pub tb: twobit::TwoBitMemoryFile
+    tb: Arc<Reference>,
+    chrom_names: Arc<Vec<String>>,
+    chrom_sizes: Arc<HashMap<String, usize>>,
+    circular: Arc<HashSet<String>>,
 }
 
 impl Grower {
+    /// Loads a 2bit file, or an uncompressed FASTA file into memory.
     pub fn new(twobit_path: &PathBuf) -> Result<Grower, Error> {
-        match twobit::TwoBitFile::open_and_read(twobit_path) {
-            Ok(tb) => Ok(Grower { tb }),
-            Err(e) => Err(Error::TwoBitError(e)),
+        if fasta::is_fasta(twobit_path) {
+            return Ok(Grower::from_fasta(fasta::read_fasta(twobit_path)?));
+        }
+        let tb = TwoBitPool::open(twobit_path).map_err(Error::TwoBitError)?;
+        let (chrom_names, chrom_sizes) = tb
+            .with_reader(|v| Ok((v.chrom_names(), v.chrom_sizes())))
+            .map_err(Error::TwoBitError)?;
+        let chrom_sizes: HashMap<String, usize> =
+            chrom_names.iter().cloned().zip(chrom_sizes).collect();
+        Ok(Grower {
+            tb: Arc::new(Reference::TwoBit(tb)),
+            chrom_names: Arc::new(chrom_names),
+            chrom_sizes: Arc::new(chrom_sizes),
+            circular: Arc::new(HashSet::default()),
+        })
+    }
+
+    fn from_fasta(sequences: Vec<(String, Vec<u8>)>) -> Grower {
+        let chrom_names: Vec<String> = sequences.iter().map(|(name, _)| name.clone()).collect();
+        let chrom_sizes: HashMap<String, usize> = sequences
+            .iter()
+            .map(|(name, seq)| (name.clone(), seq.len()))
+            .collect();
+        Grower {
+            tb: Arc::new(Reference::Fasta(sequences.into_iter().collect())),
+            chrom_names: Arc::new(chrom_names),
+            chrom_sizes: Arc::new(chrom_sizes),
+            circular: Arc::new(HashSet::default()),
+        }
+    }
+
+    /// Names of the reference sequences in file order.
+    pub fn chrom_names(&self) -> &[String] {
+        &self.chrom_names
+    }
+
+    pub fn chrom_size(&self, chrom: &str) -> Option<usize> {
+        self.chrom_sizes.get(chrom).copied()
+    }
+
+    /// Marks a sequence as circular, such as a mitochondrion or a plasmid.
+    pub fn set_circular(&mut self, chrom: &str, circular: bool) {
+        let set = Arc::make_mut(&mut self.circular);
+        match circular {
+            true => set.insert(chrom.to_string()),
+            false => set.remove(chrom),
+        };
+    }
+
+    pub fn is_circular(&self, chrom: &str) -> bool {
+        self.circular.contains(chrom)
+    }
+
+    /// Length of a circular sequence, or `None` for a linear one.
+    fn get_circular_size(&self, chrom: &str) -> Option<usize> {
+        match self.is_circular(chrom) {
+            true => self.chrom_size(chrom).filter(|v| *v > 0),
+            false => None,
+        }
+    }
+
+    /// Maps a 1-based position on a circular sequence back into `1..=size`.
+    pub fn wrap_pos(&self, chrom: &str, pos: usize) -> usize {
+        match self.get_circular_size(chrom) {
+            Some(size) => (pos - 1) % size + 1,
+            None => pos,
+        }
+    }
+
+    /// Reads a 0-based, half-open range. On circular sequences the range may
+    /// extend past the end of the sequence and continues from its start.
+    fn read_fragment(&self, chrom: &str, start: usize, end: usize) -> Result<String, Error> {
+        let size = match self.get_circular_size(chrom) {
+            Some(v) => v,
+            None => return self.read_sequence(chrom, start..end).map_err(Error::from),
+        };
+        let mut frag = String::with_capacity(end.saturating_sub(start));
+        let mut piece_start = start % size;
+        let mut remaining = end.saturating_sub(start);
+        while remaining > 0 {
+            let piece_len = std::cmp::min(remaining, size - piece_start);
+            frag.push_str(
+                &self
+                    .read_sequence(chrom, piece_start..piece_start + piece_len)
+                    .map_err(Error::from)?,
+            );
+            piece_start = 0;
+            remaining -= piece_len;
+        }
+        Ok(frag)
+    }
+
+    /// Checks that `len` bases from the 1-based `pos` lie within `chrom`. An
+    /// insertion (`len` of 0) may be placed right after the last base. On
+    /// circular sequences the bases may run past the end of the sequence.
+    pub fn check_bounds(&self, chrom: &str, pos: usize, len: usize) -> Result<(), Error> {
+        let size = match self.chrom_size(chrom) {
+            Some(v) => v,
+            None => {
+                return Err(Error::UnknownSequence {
+                    name: chrom.to_string(),
+                })
+            }
+        };
+        let in_bounds = match self.is_circular(chrom) {
+            true => pos >= 1 && pos <= size + 1 && len <= size,
+            false => pos >= 1 && pos.saturating_add(len) <= size + 1,
+        };
+        match in_bounds {
+            true => Ok(()),
+            false => Err(Error::OutOfBounds {
+                chrom: chrom.to_string(),
+                pos,
+                len,
+            }),
         }
     }
 
+    /// Reads `len` bases from the 1-based `pos`. Reads on circular sequences
+    /// may run past the end of the sequence.
+    pub fn read_bases(&self, chrom: &str, pos: usize, len: usize) -> Result<String, Error> {
+        self.check_bounds(chrom, pos, len)?;
+        self.read_fragment(chrom, pos - 1, pos - 1 + len)
+    }
+
+    /// Reads a 0-based, half-open range of a reference sequence.
+    pub fn read_sequence(
+        &self,
+        chrom: &str,
+        range: std::ops::Range<usize>,
+    ) -> Result<String, twobit::Error> {
+        match self.tb.as_ref() {
+            Reference::TwoBit(tb) => tb.with_reader(|tb| tb.read_sequence(chrom, range)),
+            // Like the 2bit reader, ranges are clipped to the sequence.
+            Reference::Fasta(sequences) => match sequences.get(chrom) {
+                Some(seq) => {
+                    let end = std::cmp::min(range.end, seq.len());
+                    let start = std::cmp::min(range.start, end);
+                    Ok(String::from_utf8_lossy(&seq[start..end]).to_string())
+                }
+                None => Err(twobit::Error::MissingName(chrom.to_string())),
+            },
+        }
+    }
+
+    #[allow(dead_code)]
     pub fn grow_right(
-        &mut self,
+        &self,
+        chrom: &[u8],
+        pos: usize,
+        bases: &[Base],
+    ) -> Result<(usize, Box<[Base]>), Error> {
+        let end = self.grow_right_virtual(chrom, pos, bases)?;
+        let chrom_str = get_chrom_str(chrom)?;
+        Ok((self.wrap_pos(chrom_str, end), cycle_right(bases, end - pos)))
+    }
+
+    #[allow(dead_code)]
+    pub fn grow_left(
+        &self,
         chrom: &[u8],
         pos: usize,
         bases: &[Base],
     ) -> Result<(usize, Box<[Base]>), Error> {
+        let chrom_str = get_chrom_str(chrom)?;
+        let offset = self.get_circular_size(chrom_str).unwrap_or(0);
+        let start = self.grow_left_virtual(chrom, pos + offset, bases)?;
+        Ok((self.wrap_pos(chrom_str, start), cycle_left(bases, pos + offset - start)))
+    }
+
+    /// Grows to the right and returns the end of the growth. The bases grown
+    /// over are `bases` repeated from its first base. On circular sequences
+    /// positions past the end of the sequence are not wrapped.
+    fn grow_right_virtual(&self, chrom: &[u8], pos: usize, bases: &[Base]) -> Result<usize, Error> {
         let bases_len = bases.len();
-        let mut expansion: Vec<Base> = Vec::with_capacity(bases_len);
         match bases_len {
-            0 => Ok((pos, expansion.into_boxed_slice())),
+            0 => Ok(pos),
             _ => {
                 // 0-based
                 let mut probe_start = pos;
                 let mut probe_end = pos + bases_len;
                 let mut growth_end = probe_start;
                 let mut grow_c: usize = 0;
-                let chrom_str = std::str::from_utf8(chrom).unwrap();
+                let chrom_str = get_chrom_str(chrom)?;
+                if pos == 0 {
+                    return Err(Error::OutOfBounds {
+                        chrom: chrom_str.to_string(),
+                        pos,
+                        len: bases_len,
+                    });
+                }
                 loop {
-                    let frag = self.tb.read_sequence(chrom_str, probe_start - 1..probe_end - 1).unwrap_or("".to_string());
-                    if frag.len() == 0 {
-                        return Ok((growth_end, expansion.into_boxed_slice()));
+                    let frag = self.read_fragment(chrom_str, probe_start - 1, probe_end - 1)?;
+                    // The end of the sequence
+                    if frag.is_empty() {
+                        growth_end = probe_start;
+                        break;
                     }
                     let check_len: usize = std::cmp::min(frag.len(), bases_len);
                     let mut chars = frag.chars();
@@ -58,7 +286,6 @@ impl Grower {
                                     diff_found = true;
                                     break;
                                 }
-                                expansion.push(base.clone());
                             }
                             None => {
                                 return Err(Error::InvalidPosition {
@@ -71,7 +298,11 @@ impl Grower {
                     if diff_found {
                         break;
                     }
-                    probe_start = probe_start + bases_len;
+                    if frag.len() < bases_len {
+                        growth_end = probe_start + frag.len();
+                        break;
+                    }
+                    probe_start += bases_len;
                     probe_end = probe_start + bases_len;
                     grow_c += 1;
                     if grow_c > GROW_RIGHT_MAX {
@@ -82,26 +313,24 @@ impl Grower {
                         });
                     }
                 }
-                Ok((growth_end, expansion.into_boxed_slice()))
+                Ok(growth_end)
             }
         }
     }
 
-    pub fn grow_left(
-        &mut self,
-        chrom: &[u8],
-        pos: usize,
-        bases: &[Base],
-    ) -> Result<(usize, Box<[Base]>), Error> {
+    /// Grows to the left and returns the start of the growth. The bases grown
+    /// over are `bases` repeated back from its last base. Callers shift
+    /// positions on circular sequences by the sequence length so that growth
+    /// can continue past the origin.
+    fn grow_left_virtual(&self, chrom: &[u8], pos: usize, bases: &[Base]) -> Result<usize, Error> {
         let bases_len = bases.len();
         let mut growth_start = pos;
-        let mut expansion: Vec<Base> = Vec::with_capacity(bases_len);
         let mut probe_end = pos;
         if bases_len == 0 {
-            return Ok((growth_start, expansion.into_boxed_slice()));
+            return Ok(growth_start);
         }
         if probe_end <= 1 {
-            return Ok((growth_start, expansion.into_boxed_slice()));
+            return Ok(growth_start);
         }
         let mut probe_start: usize;
         if probe_end <= bases_len {
@@ -109,12 +338,12 @@ impl Grower {
         } else {
             probe_start = probe_end - bases_len;
         }
-        let chrom_str = std::str::from_utf8(chrom).unwrap();
+        let chrom_str = get_chrom_str(chrom)?;
         loop {
-            match self.tb.read_sequence(chrom_str, probe_start - 1..probe_end - 1) {
+            match self.read_fragment(chrom_str, probe_start - 1, probe_end - 1) {
                 Ok(frag) => {
-                    if frag.len() == 0 {
-                        return Ok((growth_start, expansion.into_boxed_slice()));
+                    if frag.is_empty() {
+                        break;
                     }
                     let mut chars = frag.chars().rev();
                     let mut bases_iter = bases.iter().rev();
@@ -129,7 +358,6 @@ impl Grower {
                                     diff_found = true;
                                     break;
                                 }
-                                expansion.push(base.clone());
                             }
                             None => {
                                 return Err(Error::InvalidPosition {
@@ -142,97 +370,100 @@ impl Grower {
                     if diff_found {
                         break;
                     }
-                    if probe_start <= 1 || probe_start <= bases_len {
-                        return Ok((growth_start, expansion.into_boxed_slice()));
-                    }
-                    probe_end = probe_start;
-                    if probe_end <= 1 {
+                    growth_start = probe_start;
+                    // The start of the sequence
+                    if probe_start <= 1 {
                         break;
                     }
+                    probe_end = probe_start;
                     if probe_start <= bases_len {
                         probe_start = 1;
                     } else {
-                        probe_start = probe_start - bases_len;
+                        probe_start -= bases_len;
                     }
                 },
-                Err(_) => {
-                    return Err(Error::InvalidPosition {
-                        chrom: chrom_str.to_string(),
-                        pos: probe_start,
-                    });
+                Err(e) => {
+                    return Err(e);
                 }
             }
         }
-        expansion.reverse();
-        Ok((growth_start, expansion.into_boxed_slice()))
+        Ok(growth_start)
     }
 
-    pub fn grow(
-        &mut self,
+    /// Returns the start of the fully justified extent of an indel with the
+    /// numbers of bases it grows to the left and to the right, without reading
+    /// the grown bases into memory.
+    pub fn grow_extent(
+        &self,
         chrom: &[u8],
         pos: usize,
         ref_bases: &[Base],
         alt_bases: &[Base],
-    ) -> Result<(usize, Box<[Base]>, Box<[Base]>), Error> {
+    ) -> Result<(usize, usize, usize), Error> {
         let growth_left_start: usize;
-        let growth_right_end: usize;
-        let growth_left_bases: Box<[Base]>;
-        let growth_right_bases: Box<[Base]>;
         let growth_right_size: usize;
         let ref_bases_len = ref_bases.len();
         let alt_bases_len = alt_bases.len();
+        let chrom_str = get_chrom_str(chrom)?;
+        let offset = self.get_circular_size(chrom_str).unwrap_or(0);
         match ref_bases_len {
             0 => match alt_bases_len {
                 0 => {
-                    return Err(Error::EmptyVariant { chrom: String::from_utf8(chrom.to_vec()).unwrap(), pos, ref_base: format!("{:?}", ref_bases), alt_base: format!("{:?}", alt_bases) });
+                    return Err(Error::EmptyVariant { chrom: chrom_str.to_string(), pos, ref_base: format!("{:?}", ref_bases), alt_base: format!("{:?}", alt_bases) });
                 }
                 _ => {
-                    match self.grow_right(chrom, pos, alt_bases) {
-                        Ok((end, bases)) => {
-                            growth_right_end = end;
-                            growth_right_bases = bases;
-                        }
-                        Err(e) => {
-                            eprintln!(
-                                "Error: {}. {}:{}:{:?}:{:?}",
-                                e, String::from_utf8(chrom.to_vec()).unwrap(), pos, ref_bases, alt_bases
-                            );
-                            return Err(e);
-                        }
-                    }
-                    match self.grow_left(chrom, pos, alt_bases) {
-                        Ok((start, bases)) => {
-                            growth_left_start = start;
-                            growth_left_bases = bases;
-                        }
-                        Err(e) => {
-                            eprintln!(
-                                "Error: {}. {}:{}:{:?}:{:?}",
-                                e, String::from_utf8(chrom.to_vec()).unwrap(), pos, ref_bases, alt_bases
-                            );
-                            return Err(e);
-                        }
-                    }
+                    let growth_right_end = self.grow_right_virtual(chrom, pos, alt_bases)?;
+                    growth_left_start = self.grow_left_virtual(chrom, pos + offset, alt_bases)?;
                     growth_right_size = growth_right_end - pos;
                 }
             },
             _ => match alt_bases_len {
                 0 => {
                     let growth_right_start: usize = pos + ref_bases_len;
-                    (growth_right_end, growth_right_bases) =
-                        self.grow_right(chrom, growth_right_start, ref_bases)?;
-                    (growth_left_start, growth_left_bases) =
-                        self.grow_left(chrom, pos, ref_bases)?;
-                    growth_right_size = growth_right_end - pos - ref_bases_len + 1;
+                    let growth_right_end =
+                        self.grow_right_virtual(chrom, growth_right_start, ref_bases)?;
+                    growth_left_start = self.grow_left_virtual(chrom, pos + offset, ref_bases)?;
+                    growth_right_size = growth_right_end - growth_right_start;
                 }
                 _ => {
-                    return Err(Error::NotIndel { chrom: String::from_utf8(chrom.to_vec()).unwrap(), pos, ref_base: format!("{:?}", ref_bases), alt_base: format!("{:?}", alt_bases) });
+                    return Err(Error::NotIndel { chrom: chrom_str.to_string(), pos, ref_base: format!("{:?}", ref_bases), alt_base: format!("{:?}", alt_bases) });
                 }
             },
         }
-        let growth_left_size = pos - growth_left_start;
-        let new_ref_bases_len = growth_left_size + ref_bases_len + growth_right_size;
-        let new_alt_bases_len = growth_left_size + alt_bases_len + growth_right_size;
+        let growth_left_size = pos + offset - growth_left_start;
+        Ok((self.wrap_pos(chrom_str, growth_left_start), growth_left_size, growth_right_size))
+    }
+
+    #[allow(dead_code)]
+    pub fn grow(
+        &self,
+        chrom: &[u8],
+        pos: usize,
+        ref_bases: &[Base],
+        alt_bases: &[Base],
+    ) -> Result<SpdiConversion, Error> {
+        Ok(self.grow_with_extent(chrom, pos, ref_bases, alt_bases)?.0)
+    }
+
+    /// Same as `grow` but also returns the numbers of bases grown to the left
+    /// and to the right.
+    pub fn grow_with_extent(
+        &self,
+        chrom: &[u8],
+        pos: usize,
+        ref_bases: &[Base],
+        alt_bases: &[Base],
+    ) -> Result<(SpdiConversion, usize, usize), Error> {
+        let (growth_left_start, growth_left_size, growth_right_size) =
+            self.grow_extent(chrom, pos, ref_bases, alt_bases)?;
+        let indel_bases = match ref_bases.is_empty() {
+            true => alt_bases,
+            false => ref_bases,
+        };
+        let growth_left_bases = cycle_left(indel_bases, growth_left_size);
+        let growth_right_bases = cycle_right(indel_bases, growth_right_size);
+        let new_ref_bases_len = growth_left_size + ref_bases.len() + growth_right_size;
+        let new_alt_bases_len = growth_left_size + alt_bases.len() + growth_right_size;
         let mut new_ref_bases: Vec<Base> = Vec::with_capacity(new_ref_bases_len);
         let mut new_alt_bases: Vec<Base> = Vec::with_capacity(new_alt_bases_len);
         new_ref_bases.extend(growth_left_bases.iter());
@@ -241,8 +472,30 @@ impl Grower {
         new_alt_bases.extend(growth_left_bases.iter());
         new_alt_bases.extend(alt_bases.iter());
         new_alt_bases.extend(growth_right_bases.iter());
-        Ok((growth_left_start, new_ref_bases.into_boxed_slice(), new_alt_bases.into_boxed_slice()))
+        Ok((
+            (growth_left_start, new_ref_bases.into_boxed_slice(), new_alt_bases.into_boxed_slice()),
+            growth_left_size,
+            growth_right_size,
+        ))
+    }
+}
+
+/// Returns `len` bases which continue `bases` to the right, repeating it from
+/// its first base.
+fn cycle_right(bases: &[Base], len: usize) -> Box<[Base]> {
+    bases.iter().cycle().take(len).copied().collect()
+}
+
+/// Returns `len` bases which continue `bases` to the left, repeating it back
+/// from its last base.
+fn cycle_left(bases: &[Base], len: usize) -> Box<[Base]> {
+    let bases_len = bases.len();
+    if len == 0 {
+        return Box::new([]);
     }
+    (0..len)
+        .map(|i| bases[(i + bases_len - len % bases_len) % bases_len])
+        .collect()
 }
 
 #[cfg(test)]
@@ -252,22 +505,22 @@ mod tests_grow {
     #[test]
     fn test_grow_left() {
         let twobit_fname = std::env::var("TWOBIT_FNAME").unwrap();
-        let mut grower = Grower::new(&std::path::PathBuf::from(twobit_fname)).unwrap();
-        let result = grower.grow_left("chr19_GL383575v2_alt".as_bytes(), 1, &vec![Base::C, Base::A, Base::C, Base::A]).unwrap();
+        let grower = Grower::new(&std::path::PathBuf::from(twobit_fname)).unwrap();
+        let result = grower.grow_left("chr19_GL383575v2_alt".as_bytes(), 1, &[Base::C, Base::A, Base::C, Base::A]).unwrap();
         assert_eq!(result, (1, vec![].into_boxed_slice()));
-        let result = grower.grow_left("chr19_GL383575v2_alt".as_bytes(), 3, &vec![Base::A, Base::G, Base::C, Base::C]).unwrap();
-        assert_eq!(result, (3, vec![Base::C, Base::C].into_boxed_slice()));
+        let result = grower.grow_left("chr19_GL383575v2_alt".as_bytes(), 3, &[Base::A, Base::G, Base::C, Base::C]).unwrap();
+        assert_eq!(result, (1, vec![Base::C, Base::C].into_boxed_slice()));
     }
 
     #[test]
     fn test_grow_right() {
         let twobit_fname = std::env::var("TWOBIT_FNAME").unwrap();
-        let mut grower = Grower::new(&std::path::PathBuf::from(twobit_fname)).unwrap();
-        let result = grower.grow_right("chr19_GL383576v1_alt".as_bytes(), 188023, &vec![Base::C, Base::A, Base::C, Base::A]).unwrap();
+        let grower = Grower::new(&std::path::PathBuf::from(twobit_fname)).unwrap();
+        let result = grower.grow_right("chr19_GL383576v1_alt".as_bytes(), 188023, &[Base::C, Base::A, Base::C, Base::A]).unwrap();
         assert_eq!(result, (188023, vec![].into_boxed_slice()));
-        let result = grower.grow_right("chr19_GL383576v1_alt".as_bytes(), 188023, &vec![Base::T]).unwrap();
-        assert_eq!(result, (188023, vec![Base::T, Base::T].into_boxed_slice()));
-        let result = grower.grow_right("chr19_GL383576v1_alt".as_bytes(), 188023, &vec![Base::T, Base::T, Base::T]).unwrap();
-        assert_eq!(result, (188023, vec![Base::T, Base::T].into_boxed_slice()));
+        let result = grower.grow_right("chr19_GL383576v1_alt".as_bytes(), 188023, &[Base::T]).unwrap();
+        assert_eq!(result, (188025, vec![Base::T, Base::T].into_boxed_slice()));
+        let result = grower.grow_right("chr19_GL383576v1_alt".as_bytes(), 188023, &[Base::T, Base::T, Base::T]).unwrap();
+        assert_eq!(result, (188025, vec![Base::T, Base::T].into_boxed_slice()));
     }
 }
@@ -10,7 +10,7 @@
 //! use spdi::error::Error;
 //!
 //! fn example() -> Result<(), Error> {
-//!     let mut s = spdi::SPDI::new("path/to/2bit/file")?;
+//!     let s = spdi::SPDI::new(&std::path::PathBuf::from("path/to/2bit/file"))?;
 //!     let spdi_string = s.get_spdi_string("chr1".as_bytes(), 99092, "C".as_bytes(),
 //!     "CT".as_bytes())?;
 //!     assert_eq!(spdi_string, "chr1:99092:C:CT");
@@ -18,157 +18,268 @@
 //! }
 //! ```
 
+pub mod capi;
+pub mod class;
+pub mod compact;
+pub mod contig;
 pub mod error;
+mod fasta;
 mod grow;
+#[cfg(feature = "python")]
+mod python;
+pub mod repeat;
+#[cfg(test)]
 mod tests;
 mod trim;
 pub mod util;
+pub use class::{ClassifiedSpdi, NormalizationShifts, VariantClass};
+pub use compact::{CompactSequence, CompactSpdi};
+pub use repeat::TandemRepeat;
 use grow::Grower;
 pub use noodles::vcf;
 pub type Base = vcf::record::reference_bases::base::Base;
+/// Position, reference bases and alternate bases of a converted variant.
+pub type SpdiConversion = (usize, Box<[Base]>, Box<[Base]>);
 use trim::{trim_left, trim_right};
-use util::{get_bases_of_vu8, get_string_of_bases};
+use util::{get_bases_of_vu8, get_chrom_str, get_string_of_bases};
+use ahash::HashMap;
 use error::Error;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
+/// Adds the variant being converted to an error from growing it. The library
+/// does not print errors. They are logged at debug level for applications
+/// which install a `log` logger.
+fn get_conversion_error(
+    e: Error,
+    chrom: &str,
+    pos: usize,
+    ref_bases: &[Base],
+    alt_bases: &[Base],
+) -> Error {
+    let e = Error::Conversion {
+        chrom: chrom.to_string(),
+        pos,
+        ref_base: get_string_of_bases(ref_bases),
+        alt_base: get_string_of_bases(alt_bases),
+        source: Box::new(e),
+    };
+    log::debug!("{}", e);
+    e
+}
+
+/// Trims the bases shared at the end and then at the start of the reference
+/// and alternate bases. Returns the number of bases trimmed at the start with
+/// the remaining bases.
+fn shrink<'a>(ref_bases: &'a [Base], alt_bases: &'a [Base]) -> (usize, &'a [Base], &'a [Base]) {
+    let (new_ref_end, new_alt_end) =
+        trim_right(ref_bases, alt_bases, 0, ref_bases.len(), 0, alt_bases.len());
+    let (new_ref_start, new_alt_start) =
+        trim_left(ref_bases, alt_bases, 0, new_ref_end, 0, new_alt_end);
+    (
+        new_ref_start,
+        &ref_bases[new_ref_start..new_ref_end],
+        &alt_bases[new_alt_start..new_alt_end],
+    )
+}
+
+/// SPDI converter backed by an in-memory 2bit reference.
+///
+/// All conversion methods take `&self`, and `SPDI` is `Send + Sync`. Clones
+/// share the loaded reference, so one genome can serve many threads.
+#[derive(Clone)]
 pub struct SPDI {
     grower: Grower,
+    md5s: Arc<Mutex<HashMap<String, String>>>,
 }
 
 impl SPDI {
     pub fn new(twobit_path: &PathBuf) -> std::result::Result<SPDI, Error> {
         let grower = Grower::new(twobit_path)?;
-        Ok(SPDI { grower })
+        Ok(SPDI {
+            grower,
+            md5s: Arc::new(Mutex::new(HashMap::default())),
+        })
+    }
+
+    /// Names of the sequences in the reference.
+    pub fn sequence_names(&self) -> &[String] {
+        self.grower.chrom_names()
+    }
+
+    /// Returns a resolver of contig names to the sequence names of this reference.
+    pub fn contig_resolver(&self) -> contig::ContigResolver {
+        let sequences: Vec<(String, usize)> = self
+            .sequence_names()
+            .iter()
+            .map(|name| (name.clone(), self.sequence_length(name).unwrap_or(0)))
+            .collect();
+        contig::ContigResolver::new(&sequences)
+    }
+
+    /// Marks a sequence as circular, such as a mitochondrion or a plasmid.
+    /// Indels on circular sequences are grown across the origin, and positions
+    /// are reported modulo the sequence length. Clones made before this call
+    /// keep their own flags.
+    pub fn set_circular(&mut self, chrom: &str, circular: bool) {
+        self.grower.set_circular(chrom, circular);
+    }
+
+    pub fn is_circular(&self, chrom: &str) -> bool {
+        self.grower.is_circular(chrom)
+    }
+
+    /// Length of a reference sequence, or `None` if the reference does not have it.
+    pub fn sequence_length(&self, chrom: &str) -> Option<usize> {
+        self.grower.chrom_size(chrom)
+    }
+
+    /// MD5 checksum of a reference sequence in upper case, as used by the `md5`
+    /// attribute of VCF `##contig` lines and the `M5` tag of SAM headers.
+    /// Checksums are computed once per sequence and cached.
+    pub fn sequence_md5(&self, chrom: &str) -> Result<String, Error> {
+        if let Some(md5) = self.md5s.lock().unwrap_or_else(|e| e.into_inner()).get(chrom) {
+            return Ok(md5.clone());
+        }
+        let seq = self
+            .grower
+            .read_sequence(chrom, 0..self.sequence_length(chrom).unwrap_or(0))
+            .map_err(Error::TwoBitError)?;
+        let md5 = format!("{:x}", md5::compute(seq.to_ascii_uppercase()));
+        self.md5s
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(chrom.to_string(), md5.clone());
+        Ok(md5)
+    }
+
+    /// Returns `len` reference bases from the 1-based `pos` in upper case.
+    pub fn get_reference_bases(&self, chrom: &str, pos: usize, len: usize) -> Result<String, Error> {
+        Ok(self.grower.read_bases(chrom, pos, len)?.to_ascii_uppercase())
+    }
+
+    /// Checks that `ref_bases` are the reference bases at the 1-based `pos`.
+    /// Case is ignored, and N in either matches any base.
+    pub fn check_reference(&self, chrom: &[u8], pos: usize, ref_bases: &[u8]) -> Result<(), Error> {
+        let chrom_str = get_chrom_str(chrom)?;
+        let reference = self.get_reference_bases(chrom_str, pos, ref_bases.len())?;
+        let matches = ref_bases
+            .iter()
+            .zip(reference.as_bytes())
+            .all(|(a, b)| a.eq_ignore_ascii_case(b) || a.eq_ignore_ascii_case(&b'N') || *b == b'N');
+        match matches {
+            true => Ok(()),
+            false => Err(Error::ReferenceMismatch {
+                chrom: chrom_str.to_string(),
+                pos,
+                ref_base: String::from_utf8_lossy(ref_bases).to_string(),
+                reference,
+            }),
+        }
     }
 
     pub fn get_spdi_conversion_str(
-        &mut self,
+        &self,
         chrom: &[u8],
         pos: usize,
         ref_bases: &[u8],
         alt_bases: &[u8],
-    ) -> std::result::Result<(usize, Box<[Base]>, Box<[Base]>), Error> {
-        let ref_bases_v: Vec<Base>;
-        let alt_bases_v: Vec<Base>;
-        match util::get_bases_of_vu8(ref_bases) {
-            Ok(v) => ref_bases_v = v,
-            Err(e) => {
-                return Err(e);
-            }
-        }
-        let alt_bases_q: &[u8];
-        if alt_bases[0] == '.' as u8 {
-            alt_bases_q = ref_bases;
+    ) -> std::result::Result<SpdiConversion, Error> {
+        let ref_bases_v: Vec<Base> = util::get_bases_of_vu8(ref_bases)?;
+        let alt_bases_q: &[u8] = if alt_bases.first() == Some(&b'.') {
+            ref_bases
         } else {
-            alt_bases_q = alt_bases;
-        }
-        match util::get_bases_of_vu8(alt_bases_q) {
-            Ok(v) => alt_bases_v = v,
-            Err(e) => {
-                return Err(e);
-            }
-        }
+            alt_bases
+        };
+        let alt_bases_v: Vec<Base> = util::get_bases_of_vu8(alt_bases_q)?;
         self.get_spdi_conversion(chrom, pos, &ref_bases_v, &alt_bases_v)
     }
 
     pub fn get_spdi_conversion(
-        &mut self,
+        &self,
         chrom: &[u8],
         pos: usize,
         ref_bases: &[Base],
         alt_bases: &[Base],
-    ) -> std::result::Result<(usize, Box<[Base]>, Box<[Base]>), Error> {
-        let ref_bases_len = ref_bases.len();
-        let alt_bases_len = alt_bases.len();
-        let ref_start = 0;
-        let ref_end = match ref_bases_len {
-            0 => 0,
-            _ => ref_bases_len,
-        };
-        let alt_start = 0;
-        let alt_end = match alt_bases_len {
-            0 => 0,
-            _ => alt_bases_len,
-        };
-        let new_ref_start: usize;
-        let new_ref_end: usize;
-        let new_alt_start: usize;
-        let new_alt_end: usize;
-        // shrink
-        (new_ref_end, new_alt_end) =
-            trim_right(ref_bases, alt_bases, ref_start, ref_end, alt_start, alt_end);
-        (new_ref_start, new_alt_start) = trim_left(
-            ref_bases,
-            alt_bases,
-            ref_start,
-            new_ref_end,
-            alt_start,
-            new_alt_end,
-        );
-        let shrunk_ref_bases = &ref_bases[new_ref_start..new_ref_end];
-        let shrunk_alt_bases = &alt_bases[new_alt_start..new_alt_end];
+    ) -> std::result::Result<SpdiConversion, Error> {
+        Ok(self.normalize(chrom, pos, ref_bases, alt_bases)?.0)
+    }
+
+    /// Converts a variant and returns how far each step moved its ends.
+    fn normalize(
+        &self,
+        chrom: &[u8],
+        pos: usize,
+        ref_bases: &[Base],
+        alt_bases: &[Base],
+    ) -> std::result::Result<(SpdiConversion, NormalizationShifts), Error> {
+        let chrom_str = get_chrom_str(chrom)?;
+        if ref_bases.is_empty() && alt_bases.is_empty() {
+            return Err(Error::EmptyVariant {
+                chrom: chrom_str.to_string(),
+                pos,
+                ref_base: String::new(),
+                alt_base: String::new(),
+            });
+        }
+        self.grower.check_bounds(chrom_str, pos, ref_bases.len())?;
+        let (new_ref_start, shrunk_ref_bases, shrunk_alt_bases) = shrink(ref_bases, alt_bases);
         let shrunk_pos = pos + new_ref_start;
         let shrunk_ref_bases_len = shrunk_ref_bases.len();
         let shrunk_alt_bases_len = shrunk_alt_bases.len();
-        match shrunk_ref_bases_len {
+        let mut shifts = NormalizationShifts {
+            trimmed_left: new_ref_start,
+            trimmed_right: ref_bases.len() - new_ref_start - shrunk_ref_bases_len,
+            grown_left: 0,
+            grown_right: 0,
+        };
+        let conversion = match shrunk_ref_bases_len {
             0 => {
                 match shrunk_alt_bases_len {
                     // same
                     0 => {
                         let base = &ref_bases[0..1];
-                        Ok((pos, base.to_vec().into_boxed_slice(), base.to_vec().into_boxed_slice()))
+                        let pos = self.grower.wrap_pos(chrom_str, pos);
+                        (pos, base.to_vec().into_boxed_slice(), base.to_vec().into_boxed_slice())
                     }
                     // insertion
                     _ => {
-                        match self.grower.grow(
-                            chrom,
-                            shrunk_pos,
-                            shrunk_ref_bases,
-                            shrunk_alt_bases,
-                        ) {
-                            Ok(v) => Ok(v),
-                            Err(e) => {
-                                eprintln!(
-                                    "Error: {}. {}:{}:{:?}:{:?}",
-                                    e, std::str::from_utf8(chrom).unwrap(), pos, ref_bases, alt_bases
-                                );
-                                Err(e)
-                            }
-                        }
+                        let (conversion, grown_left, grown_right) = self
+                            .grower
+                            .grow_with_extent(chrom, shrunk_pos, shrunk_ref_bases, shrunk_alt_bases)
+                            .map_err(|e| {
+                                get_conversion_error(e, chrom_str, pos, ref_bases, alt_bases)
+                            })?;
+                        (shifts.grown_left, shifts.grown_right) = (grown_left, grown_right);
+                        conversion
                     }
                 }
             }
             _ => {
                 match shrunk_alt_bases_len {
                     // deletion
-                    0 => match self.grower.grow(
-                        chrom,
-                        shrunk_pos,
-                        shrunk_ref_bases,
-                        shrunk_alt_bases,
-                    ) {
-                        Ok(v) => Ok(v),
-                        Err(e) => {
-                            eprintln!(
-                                "Error: {}. {}:{}:{:?}:{:?}",
-                                e, std::str::from_utf8(chrom).unwrap(), pos, ref_bases, alt_bases
-                            );
-                            Err(e)
-                        }
-                    },
+                    0 => {
+                        let (conversion, grown_left, grown_right) = self
+                            .grower
+                            .grow_with_extent(chrom, shrunk_pos, shrunk_ref_bases, shrunk_alt_bases)
+                            .map_err(|e| get_conversion_error(e, chrom_str, pos, ref_bases, alt_bases))?;
+                        (shifts.grown_left, shifts.grown_right) = (grown_left, grown_right);
+                        conversion
+                    }
                     // ambiguous
-                    _ => Ok((pos, shrunk_ref_bases.to_vec().into_boxed_slice(), shrunk_alt_bases.to_vec().into_boxed_slice())),
+                    _ => (self.grower.wrap_pos(chrom_str, shrunk_pos), shrunk_ref_bases.to_vec().into_boxed_slice(), shrunk_alt_bases.to_vec().into_boxed_slice()),
                 }
             }
-        }
+        };
+        Ok((conversion, shifts))
     }
 
     pub fn get_spdi_string_components(
-        &mut self,
+        &self,
         chrom: &[u8],
         pos: usize,
-        ref_bases: &Vec<Base>,
-        alt_bases: &Vec<Base>,
+        ref_bases: &[Base],
+        alt_bases: &[Base],
     ) -> Result<(usize, String, String), Error> {
         let new_pos: usize;
         let new_ref_bases: Box<[Base]>;
@@ -180,8 +291,132 @@ impl SPDI {
         Ok((new_pos, new_ref_bases_string, new_alt_bases_string))
     }
 
+    /// Same as `get_spdi_string_components` but takes bases as bytes.
+    pub fn get_spdi_string_components_str(
+        &self,
+        chrom: &[u8],
+        pos: usize,
+        ref_bases_s: &[u8],
+        alt_bases_s: &[u8],
+    ) -> Result<(usize, String, String), Error> {
+        let ref_bases = get_bases_of_vu8(ref_bases_s)?;
+        let alt_bases = get_bases_of_vu8(alt_bases_s)?;
+        self.get_spdi_string_components(chrom, pos, &ref_bases, &alt_bases)
+    }
+
+    /// Same as `get_spdi_string_components` but also returns the class of the
+    /// variant, whether it is shiftable, the repeat it changes and how far
+    /// normalization moved it.
+    pub fn get_classified_spdi(
+        &self,
+        chrom: &[u8],
+        pos: usize,
+        ref_bases: &[Base],
+        alt_bases: &[Base],
+    ) -> Result<ClassifiedSpdi, Error> {
+        let ((new_pos, new_ref_bases, new_alt_bases), shifts) =
+            self.normalize(chrom, pos, ref_bases, alt_bases)?;
+        let (_, shrunk_ref_bases, shrunk_alt_bases) = shrink(ref_bases, alt_bases);
+        let class = VariantClass::of_lens(shrunk_ref_bases.len(), shrunk_alt_bases.len());
+        let shiftable = class.is_indel() && new_ref_bases.len() > shrunk_ref_bases.len();
+        let repeat = match shiftable {
+            true => repeat::get_tandem_repeat(
+                &new_ref_bases,
+                &new_alt_bases,
+                shrunk_ref_bases.len().abs_diff(shrunk_alt_bases.len()),
+            ),
+            false => None,
+        };
+        Ok(ClassifiedSpdi {
+            pos: new_pos,
+            ref_bases: get_string_of_bases(&new_ref_bases),
+            alt_bases: get_string_of_bases(&new_alt_bases),
+            class,
+            shiftable,
+            repeat,
+            shifts,
+        })
+    }
+
+    /// Same as `get_classified_spdi` but takes bases as bytes.
+    pub fn get_classified_spdi_str(
+        &self,
+        chrom: &[u8],
+        pos: usize,
+        ref_bases_s: &[u8],
+        alt_bases_s: &[u8],
+    ) -> Result<ClassifiedSpdi, Error> {
+        let ref_bases = get_bases_of_vu8(ref_bases_s)?;
+        let alt_bases = get_bases_of_vu8(alt_bases_s)?;
+        self.get_classified_spdi(chrom, pos, &ref_bases, &alt_bases)
+    }
+
+    /// Same as `get_spdi_string_components`, but the bases of indels are given
+    /// as a motif and lengths and are not read from the reference, so indels
+    /// in long repeats take little memory. Other variants have literal bases.
+    pub fn get_compact_spdi(
+        &self,
+        chrom: &[u8],
+        pos: usize,
+        ref_bases: &[Base],
+        alt_bases: &[Base],
+    ) -> Result<CompactSpdi, Error> {
+        let (new_ref_start, shrunk_ref_bases, shrunk_alt_bases) = shrink(ref_bases, alt_bases);
+        let class = VariantClass::of_lens(shrunk_ref_bases.len(), shrunk_alt_bases.len());
+        if !class.is_indel() {
+            let (new_pos, new_ref_bases, new_alt_bases) =
+                self.get_spdi_string_components(chrom, pos, ref_bases, alt_bases)?;
+            return Ok(CompactSpdi {
+                pos: new_pos,
+                deleted: CompactSequence::Literal(new_ref_bases),
+                inserted: CompactSequence::Literal(new_alt_bases),
+            });
+        }
+        let chrom_str = get_chrom_str(chrom)?;
+        self.grower.check_bounds(chrom_str, pos, ref_bases.len())?;
+        let (new_pos, growth_left_size, growth_right_size) = self
+            .grower
+            .grow_extent(chrom, pos + new_ref_start, shrunk_ref_bases, shrunk_alt_bases)
+            .map_err(|e| get_conversion_error(e, chrom_str, pos, ref_bases, alt_bases))?;
+        let indel_bases = match shrunk_ref_bases.is_empty() {
+            true => shrunk_alt_bases,
+            false => shrunk_ref_bases,
+        };
+        // The extent starts with the indel bases rotated by the left growth.
+        let indel_len = indel_bases.len();
+        let unit: Vec<Base> = (0..indel_len)
+            .map(|i| indel_bases[(i + indel_len - growth_left_size % indel_len) % indel_len])
+            .collect();
+        let motif = get_string_of_bases(&unit[..repeat::get_period(&unit)]);
+        let growth_size = growth_left_size + growth_right_size;
+        Ok(CompactSpdi {
+            pos: new_pos,
+            deleted: CompactSequence::Repeat {
+                motif: motif.clone(),
+                len: growth_size + shrunk_ref_bases.len(),
+            },
+            inserted: CompactSequence::Repeat {
+                motif,
+                len: growth_size + shrunk_alt_bases.len(),
+            },
+        })
+    }
+
+    /// Same as `get_compact_spdi` but takes bases as bytes.
+    pub fn get_compact_spdi_str(
+        &self,
+        chrom: &[u8],
+        pos: usize,
+        ref_bases_s: &[u8],
+        alt_bases_s: &[u8],
+    ) -> Result<CompactSpdi, Error> {
+        let ref_bases = get_bases_of_vu8(ref_bases_s)?;
+        let alt_bases = get_bases_of_vu8(alt_bases_s)?;
+        self.get_compact_spdi(chrom, pos, &ref_bases, &alt_bases)
+    }
+
     pub fn get_spdi_string(
-        &mut self,
+        &self,
         chrom: &[u8],
         pos: usize,
         ref_bases_s: &[u8],
@@ -196,7 +431,7 @@ impl SPDI {
             self.get_spdi_string_components(chrom, pos, &ref_bases, &alt_bases)?;
         Ok(format!(
             "{}:{}:{}:{}",
-            std::str::from_utf8(chrom).unwrap(), new_pos, new_ref_bases_s, new_alt_bases_s
+            get_chrom_str(chrom)?, new_pos, new_ref_bases_s, new_alt_bases_s
         ))
     }
 }
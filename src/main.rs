@@ -4,532 +4,1276 @@
 //
 // To obtain a commercial license, please contact info@oakbioinformatics.com.
 
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::io::{BufRead, BufWriter, Write};
 use ahash::HashMap;
-use clap::Parser;
-use lazy_static::lazy_static;
-use std::io::BufRead;
-mod error;
-use spdi::SPDI;
+use spdi::contig::{get_contig_name, ContigNaming, ContigResolver, MitoSequence};
+use spdi::{ClassifiedSpdi, NormalizationShifts, TandemRepeat, VariantClass, SPDI};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
-lazy_static! {
-    static ref CHROMS: HashMap<&'static str, &'static str> = {
-        let mut m = HashMap::default();
-        m.insert("KI270721.1", "chr11_KI270721v1_random");
-        m.insert("GL000009.2", "chr14_GL000009v2_random");
-        m.insert("GL000225.1", "chr14_GL000225v1_random");
-        m.insert("KI270722.1", "chr14_KI270722v1_random");
-        m.insert("GL000194.1", "chr14_GL000194v1_random");
-        m.insert("KI270723.1", "chr14_KI270723v1_random");
-        m.insert("KI270724.1", "chr14_KI270724v1_random");
-        m.insert("KI270725.1", "chr14_KI270725v1_random");
-        m.insert("KI270726.1", "chr14_KI270726v1_random");
-        m.insert("KI270727.1", "chr15_KI270727v1_random");
-        m.insert("KI270728.1", "chr16_KI270728v1_random");
-        m.insert("GL000205.2", "chr17_GL000205v2_random");
-        m.insert("KI270729.1", "chr17_KI270729v1_random");
-        m.insert("KI270730.1", "chr17_KI270730v1_random");
-        m.insert("KI270706.1", "chr1_KI270706v1_random");
-        m.insert("KI270707.1", "chr1_KI270707v1_random");
-        m.insert("KI270708.1", "chr1_KI270708v1_random");
-        m.insert("KI270709.1", "chr1_KI270709v1_random");
-        m.insert("KI270710.1", "chr1_KI270710v1_random");
-        m.insert("KI270711.1", "chr1_KI270711v1_random");
-        m.insert("KI270712.1", "chr1_KI270712v1_random");
-        m.insert("KI270713.1", "chr1_KI270713v1_random");
-        m.insert("KI270714.1", "chr1_KI270714v1_random");
-        m.insert("KI270731.1", "chr22_KI270731v1_random");
-        m.insert("KI270732.1", "chr22_KI270732v1_random");
-        m.insert("KI270733.1", "chr22_KI270733v1_random");
-        m.insert("KI270734.1", "chr22_KI270734v1_random");
-        m.insert("KI270735.1", "chr22_KI270735v1_random");
-        m.insert("KI270736.1", "chr22_KI270736v1_random");
-        m.insert("KI270737.1", "chr22_KI270737v1_random");
-        m.insert("KI270738.1", "chr22_KI270738v1_random");
-        m.insert("KI270739.1", "chr22_KI270739v1_random");
-        m.insert("KI270715.1", "chr2_KI270715v1_random");
-        m.insert("KI270716.1", "chr2_KI270716v1_random");
-        m.insert("GL000221.1", "chr3_GL000221v1_random");
-        m.insert("GL000008.2", "chr4_GL000008v2_random");
-        m.insert("GL000208.1", "chr5_GL000208v1_random");
-        m.insert("KI270717.1", "chr9_KI270717v1_random");
-        m.insert("KI270718.1", "chr9_KI270718v1_random");
-        m.insert("KI270719.1", "chr9_KI270719v1_random");
-        m.insert("KI270720.1", "chr9_KI270720v1_random");
-        m.insert("KI270762.1", "chr1_KI270762v1_alt");
-        m.insert("KI270766.1", "chr1_KI270766v1_alt");
-        m.insert("KI270760.1", "chr1_KI270760v1_alt");
-        m.insert("KI270765.1", "chr1_KI270765v1_alt");
-        m.insert("GL383518.1", "chr1_GL383518v1_alt");
-        m.insert("GL383519.1", "chr1_GL383519v1_alt");
-        m.insert("GL383520.2", "chr1_GL383520v2_alt");
-        m.insert("KI270764.1", "chr1_KI270764v1_alt");
-        m.insert("KI270763.1", "chr1_KI270763v1_alt");
-        m.insert("KI270759.1", "chr1_KI270759v1_alt");
-        m.insert("KI270761.1", "chr1_KI270761v1_alt");
-        m.insert("KI270770.1", "chr2_KI270770v1_alt");
-        m.insert("KI270773.1", "chr2_KI270773v1_alt");
-        m.insert("KI270774.1", "chr2_KI270774v1_alt");
-        m.insert("KI270769.1", "chr2_KI270769v1_alt");
-        m.insert("GL383521.1", "chr2_GL383521v1_alt");
-        m.insert("KI270772.1", "chr2_KI270772v1_alt");
-        m.insert("KI270775.1", "chr2_KI270775v1_alt");
-        m.insert("KI270771.1", "chr2_KI270771v1_alt");
-        m.insert("KI270768.1", "chr2_KI270768v1_alt");
-        m.insert("GL582966.2", "chr2_GL582966v2_alt");
-        m.insert("GL383522.1", "chr2_GL383522v1_alt");
-        m.insert("KI270776.1", "chr2_KI270776v1_alt");
-        m.insert("KI270767.1", "chr2_KI270767v1_alt");
-        m.insert("JH636055.2", "chr3_JH636055v2_alt");
-        m.insert("KI270783.1", "chr3_KI270783v1_alt");
-        m.insert("KI270780.1", "chr3_KI270780v1_alt");
-        m.insert("GL383526.1", "chr3_GL383526v1_alt");
-        m.insert("KI270777.1", "chr3_KI270777v1_alt");
-        m.insert("KI270778.1", "chr3_KI270778v1_alt");
-        m.insert("KI270781.1", "chr3_KI270781v1_alt");
-        m.insert("KI270779.1", "chr3_KI270779v1_alt");
-        m.insert("KI270782.1", "chr3_KI270782v1_alt");
-        m.insert("KI270784.1", "chr3_KI270784v1_alt");
-        m.insert("KI270790.1", "chr4_KI270790v1_alt");
-        m.insert("GL383528.1", "chr4_GL383528v1_alt");
-        m.insert("KI270787.1", "chr4_KI270787v1_alt");
-        m.insert("GL000257.2", "chr4_GL000257v2_alt");
-        m.insert("KI270788.1", "chr4_KI270788v1_alt");
-        m.insert("GL383527.1", "chr4_GL383527v1_alt");
-        m.insert("KI270785.1", "chr4_KI270785v1_alt");
-        m.insert("KI270789.1", "chr4_KI270789v1_alt");
-        m.insert("KI270786.1", "chr4_KI270786v1_alt");
-        m.insert("KI270793.1", "chr5_KI270793v1_alt");
-        m.insert("KI270792.1", "chr5_KI270792v1_alt");
-        m.insert("KI270791.1", "chr5_KI270791v1_alt");
-        m.insert("GL383532.1", "chr5_GL383532v1_alt");
-        m.insert("GL949742.1", "chr5_GL949742v1_alt");
-        m.insert("KI270794.1", "chr5_KI270794v1_alt");
-        m.insert("GL339449.2", "chr5_GL339449v2_alt");
-        m.insert("GL383530.1", "chr5_GL383530v1_alt");
-        m.insert("KI270796.1", "chr5_KI270796v1_alt");
-        m.insert("GL383531.1", "chr5_GL383531v1_alt");
-        m.insert("KI270795.1", "chr5_KI270795v1_alt");
-        m.insert("GL000250.2", "chr6_GL000250v2_alt");
-        m.insert("KI270800.1", "chr6_KI270800v1_alt");
-        m.insert("KI270799.1", "chr6_KI270799v1_alt");
-        m.insert("GL383533.1", "chr6_GL383533v1_alt");
-        m.insert("KI270801.1", "chr6_KI270801v1_alt");
-        m.insert("KI270802.1", "chr6_KI270802v1_alt");
-        m.insert("KB021644.2", "chr6_KB021644v2_alt");
-        m.insert("KI270797.1", "chr6_KI270797v1_alt");
-        m.insert("KI270798.1", "chr6_KI270798v1_alt");
-        m.insert("KI270804.1", "chr7_KI270804v1_alt");
-        m.insert("KI270809.1", "chr7_KI270809v1_alt");
-        m.insert("KI270806.1", "chr7_KI270806v1_alt");
-        m.insert("GL383534.2", "chr7_GL383534v2_alt");
-        m.insert("KI270803.1", "chr7_KI270803v1_alt");
-        m.insert("KI270808.1", "chr7_KI270808v1_alt");
-        m.insert("KI270807.1", "chr7_KI270807v1_alt");
-        m.insert("KI270805.1", "chr7_KI270805v1_alt");
-        m.insert("KI270818.1", "chr8_KI270818v1_alt");
-        m.insert("KI270812.1", "chr8_KI270812v1_alt");
-        m.insert("KI270811.1", "chr8_KI270811v1_alt");
-        m.insert("KI270821.1", "chr8_KI270821v1_alt");
-        m.insert("KI270813.1", "chr8_KI270813v1_alt");
-        m.insert("KI270822.1", "chr8_KI270822v1_alt");
-        m.insert("KI270814.1", "chr8_KI270814v1_alt");
-        m.insert("KI270810.1", "chr8_KI270810v1_alt");
-        m.insert("KI270819.1", "chr8_KI270819v1_alt");
-        m.insert("KI270820.1", "chr8_KI270820v1_alt");
-        m.insert("KI270817.1", "chr8_KI270817v1_alt");
-        m.insert("KI270816.1", "chr8_KI270816v1_alt");
-        m.insert("KI270815.1", "chr8_KI270815v1_alt");
-        m.insert("GL383539.1", "chr9_GL383539v1_alt");
-        m.insert("GL383540.1", "chr9_GL383540v1_alt");
-        m.insert("GL383541.1", "chr9_GL383541v1_alt");
-        m.insert("GL383542.1", "chr9_GL383542v1_alt");
-        m.insert("KI270823.1", "chr9_KI270823v1_alt");
-        m.insert("GL383545.1", "chr10_GL383545v1_alt");
-        m.insert("KI270824.1", "chr10_KI270824v1_alt");
-        m.insert("GL383546.1", "chr10_GL383546v1_alt");
-        m.insert("KI270825.1", "chr10_KI270825v1_alt");
-        m.insert("KI270832.1", "chr11_KI270832v1_alt");
-        m.insert("KI270830.1", "chr11_KI270830v1_alt");
-        m.insert("KI270831.1", "chr11_KI270831v1_alt");
-        m.insert("KI270829.1", "chr11_KI270829v1_alt");
-        m.insert("GL383547.1", "chr11_GL383547v1_alt");
-        m.insert("JH159136.1", "chr11_JH159136v1_alt");
-        m.insert("JH159137.1", "chr11_JH159137v1_alt");
-        m.insert("KI270827.1", "chr11_KI270827v1_alt");
-        m.insert("KI270826.1", "chr11_KI270826v1_alt");
-        m.insert("GL877875.1", "chr12_GL877875v1_alt");
-        m.insert("GL877876.1", "chr12_GL877876v1_alt");
-        m.insert("KI270837.1", "chr12_KI270837v1_alt");
-        m.insert("GL383549.1", "chr12_GL383549v1_alt");
-        m.insert("KI270835.1", "chr12_KI270835v1_alt");
-        m.insert("GL383550.2", "chr12_GL383550v2_alt");
-        m.insert("GL383552.1", "chr12_GL383552v1_alt");
-        m.insert("GL383553.2", "chr12_GL383553v2_alt");
-        m.insert("KI270834.1", "chr12_KI270834v1_alt");
-        m.insert("GL383551.1", "chr12_GL383551v1_alt");
-        m.insert("KI270833.1", "chr12_KI270833v1_alt");
-        m.insert("KI270836.1", "chr12_KI270836v1_alt");
-        m.insert("KI270840.1", "chr13_KI270840v1_alt");
-        m.insert("KI270839.1", "chr13_KI270839v1_alt");
-        m.insert("KI270843.1", "chr13_KI270843v1_alt");
-        m.insert("KI270841.1", "chr13_KI270841v1_alt");
-        m.insert("KI270838.1", "chr13_KI270838v1_alt");
-        m.insert("KI270842.1", "chr13_KI270842v1_alt");
-        m.insert("KI270844.1", "chr14_KI270844v1_alt");
-        m.insert("KI270847.1", "chr14_KI270847v1_alt");
-        m.insert("KI270845.1", "chr14_KI270845v1_alt");
-        m.insert("KI270846.1", "chr14_KI270846v1_alt");
-        m.insert("KI270852.1", "chr15_KI270852v1_alt");
-        m.insert("KI270851.1", "chr15_KI270851v1_alt");
-        m.insert("KI270848.1", "chr15_KI270848v1_alt");
-        m.insert("GL383554.1", "chr15_GL383554v1_alt");
-        m.insert("KI270849.1", "chr15_KI270849v1_alt");
-        m.insert("GL383555.2", "chr15_GL383555v2_alt");
-        m.insert("KI270850.1", "chr15_KI270850v1_alt");
-        m.insert("KI270854.1", "chr16_KI270854v1_alt");
-        m.insert("KI270856.1", "chr16_KI270856v1_alt");
-        m.insert("KI270855.1", "chr16_KI270855v1_alt");
-        m.insert("KI270853.1", "chr16_KI270853v1_alt");
-        m.insert("GL383556.1", "chr16_GL383556v1_alt");
-        m.insert("GL383557.1", "chr16_GL383557v1_alt");
-        m.insert("GL383563.3", "chr17_GL383563v3_alt");
-        m.insert("KI270862.1", "chr17_KI270862v1_alt");
-        m.insert("KI270861.1", "chr17_KI270861v1_alt");
-        m.insert("KI270857.1", "chr17_KI270857v1_alt");
-        m.insert("JH159146.1", "chr17_JH159146v1_alt");
-        m.insert("JH159147.1", "chr17_JH159147v1_alt");
-        m.insert("GL383564.2", "chr17_GL383564v2_alt");
-        m.insert("GL000258.2", "chr17_GL000258v2_alt");
-        m.insert("GL383565.1", "chr17_GL383565v1_alt");
-        m.insert("KI270858.1", "chr17_KI270858v1_alt");
-        m.insert("KI270859.1", "chr17_KI270859v1_alt");
-        m.insert("GL383566.1", "chr17_GL383566v1_alt");
-        m.insert("KI270860.1", "chr17_KI270860v1_alt");
-        m.insert("KI270864.1", "chr18_KI270864v1_alt");
-        m.insert("GL383567.1", "chr18_GL383567v1_alt");
-        m.insert("GL383570.1", "chr18_GL383570v1_alt");
-        m.insert("GL383571.1", "chr18_GL383571v1_alt");
-        m.insert("GL383568.1", "chr18_GL383568v1_alt");
-        m.insert("GL383569.1", "chr18_GL383569v1_alt");
-        m.insert("GL383572.1", "chr18_GL383572v1_alt");
-        m.insert("KI270863.1", "chr18_KI270863v1_alt");
-        m.insert("KI270868.1", "chr19_KI270868v1_alt");
-        m.insert("KI270865.1", "chr19_KI270865v1_alt");
-        m.insert("GL383573.1", "chr19_GL383573v1_alt");
-        m.insert("GL383575.2", "chr19_GL383575v2_alt");
-        m.insert("GL383576.1", "chr19_GL383576v1_alt");
-        m.insert("GL383574.1", "chr19_GL383574v1_alt");
-        m.insert("KI270866.1", "chr19_KI270866v1_alt");
-        m.insert("KI270867.1", "chr19_KI270867v1_alt");
-        m.insert("GL949746.1", "chr19_GL949746v1_alt");
-        m.insert("GL383577.2", "chr20_GL383577v2_alt");
-        m.insert("KI270869.1", "chr20_KI270869v1_alt");
-        m.insert("KI270871.1", "chr20_KI270871v1_alt");
-        m.insert("KI270870.1", "chr20_KI270870v1_alt");
-        m.insert("GL383578.2", "chr21_GL383578v2_alt");
-        m.insert("KI270874.1", "chr21_KI270874v1_alt");
-        m.insert("KI270873.1", "chr21_KI270873v1_alt");
-        m.insert("GL383579.2", "chr21_GL383579v2_alt");
-        m.insert("GL383580.2", "chr21_GL383580v2_alt");
-        m.insert("GL383581.2", "chr21_GL383581v2_alt");
-        m.insert("KI270872.1", "chr21_KI270872v1_alt");
-        m.insert("KI270875.1", "chr22_KI270875v1_alt");
-        m.insert("KI270878.1", "chr22_KI270878v1_alt");
-        m.insert("KI270879.1", "chr22_KI270879v1_alt");
-        m.insert("KI270876.1", "chr22_KI270876v1_alt");
-        m.insert("KI270877.1", "chr22_KI270877v1_alt");
-        m.insert("GL383583.2", "chr22_GL383583v2_alt");
-        m.insert("GL383582.2", "chr22_GL383582v2_alt");
-        m.insert("KI270880.1", "chrX_KI270880v1_alt");
-        m.insert("KI270881.1", "chrX_KI270881v1_alt");
-        m.insert("KI270882.1", "chr19_KI270882v1_alt");
-        m.insert("KI270883.1", "chr19_KI270883v1_alt");
-        m.insert("KI270884.1", "chr19_KI270884v1_alt");
-        m.insert("KI270885.1", "chr19_KI270885v1_alt");
-        m.insert("KI270886.1", "chr19_KI270886v1_alt");
-        m.insert("KI270887.1", "chr19_KI270887v1_alt");
-        m.insert("KI270888.1", "chr19_KI270888v1_alt");
-        m.insert("KI270889.1", "chr19_KI270889v1_alt");
-        m.insert("KI270890.1", "chr19_KI270890v1_alt");
-        m.insert("KI270891.1", "chr19_KI270891v1_alt");
-        m.insert("KI270892.1", "chr1_KI270892v1_alt");
-        m.insert("KI270894.1", "chr2_KI270894v1_alt");
-        m.insert("KI270893.1", "chr2_KI270893v1_alt");
-        m.insert("KI270895.1", "chr3_KI270895v1_alt");
-        m.insert("KI270896.1", "chr4_KI270896v1_alt");
-        m.insert("KI270897.1", "chr5_KI270897v1_alt");
-        m.insert("KI270898.1", "chr5_KI270898v1_alt");
-        m.insert("GL000251.2", "chr6_GL000251v2_alt");
-        m.insert("KI270899.1", "chr7_KI270899v1_alt");
-        m.insert("KI270901.1", "chr8_KI270901v1_alt");
-        m.insert("KI270900.1", "chr8_KI270900v1_alt");
-        m.insert("KI270902.1", "chr11_KI270902v1_alt");
-        m.insert("KI270903.1", "chr11_KI270903v1_alt");
-        m.insert("KI270904.1", "chr12_KI270904v1_alt");
-        m.insert("KI270906.1", "chr15_KI270906v1_alt");
-        m.insert("KI270905.1", "chr15_KI270905v1_alt");
-        m.insert("KI270907.1", "chr17_KI270907v1_alt");
-        m.insert("KI270910.1", "chr17_KI270910v1_alt");
-        m.insert("KI270909.1", "chr17_KI270909v1_alt");
-        m.insert("JH159148.1", "chr17_JH159148v1_alt");
-        m.insert("KI270908.1", "chr17_KI270908v1_alt");
-        m.insert("KI270912.1", "chr18_KI270912v1_alt");
-        m.insert("KI270911.1", "chr18_KI270911v1_alt");
-        m.insert("GL949747.2", "chr19_GL949747v2_alt");
-        m.insert("KB663609.1", "chr22_KB663609v1_alt");
-        m.insert("KI270913.1", "chrX_KI270913v1_alt");
-        m.insert("KI270914.1", "chr19_KI270914v1_alt");
-        m.insert("KI270915.1", "chr19_KI270915v1_alt");
-        m.insert("KI270916.1", "chr19_KI270916v1_alt");
-        m.insert("KI270917.1", "chr19_KI270917v1_alt");
-        m.insert("KI270918.1", "chr19_KI270918v1_alt");
-        m.insert("KI270919.1", "chr19_KI270919v1_alt");
-        m.insert("KI270920.1", "chr19_KI270920v1_alt");
-        m.insert("KI270921.1", "chr19_KI270921v1_alt");
-        m.insert("KI270922.1", "chr19_KI270922v1_alt");
-        m.insert("KI270923.1", "chr19_KI270923v1_alt");
-        m.insert("KI270924.1", "chr3_KI270924v1_alt");
-        m.insert("KI270925.1", "chr4_KI270925v1_alt");
-        m.insert("GL000252.2", "chr6_GL000252v2_alt");
-        m.insert("KI270926.1", "chr8_KI270926v1_alt");
-        m.insert("KI270927.1", "chr11_KI270927v1_alt");
-        m.insert("GL949748.2", "chr19_GL949748v2_alt");
-        m.insert("KI270928.1", "chr22_KI270928v1_alt");
-        m.insert("KI270929.1", "chr19_KI270929v1_alt");
-        m.insert("KI270930.1", "chr19_KI270930v1_alt");
-        m.insert("KI270931.1", "chr19_KI270931v1_alt");
-        m.insert("KI270932.1", "chr19_KI270932v1_alt");
-        m.insert("KI270933.1", "chr19_KI270933v1_alt");
-        m.insert("GL000209.2", "chr19_GL000209v2_alt");
-        m.insert("KI270934.1", "chr3_KI270934v1_alt");
-        m.insert("GL000253.2", "chr6_GL000253v2_alt");
-        m.insert("GL949749.2", "chr19_GL949749v2_alt");
-        m.insert("KI270935.1", "chr3_KI270935v1_alt");
-        m.insert("GL000254.2", "chr6_GL000254v2_alt");
-        m.insert("GL949750.2", "chr19_GL949750v2_alt");
-        m.insert("KI270936.1", "chr3_KI270936v1_alt");
-        m.insert("GL000255.2", "chr6_GL000255v2_alt");
-        m.insert("GL949751.2", "chr19_GL949751v2_alt");
-        m.insert("KI270937.1", "chr3_KI270937v1_alt");
-        m.insert("GL000256.2", "chr6_GL000256v2_alt");
-        m.insert("GL949752.1", "chr19_GL949752v1_alt");
-        m.insert("KI270758.1", "chr6_KI270758v1_alt");
-        m.insert("GL949753.2", "chr19_GL949753v2_alt");
-        m.insert("KI270938.1", "chr19_KI270938v1_alt");
-        m.insert("KI270302.1", "chrUn_KI270302v1");
-        m.insert("KI270304.1", "chrUn_KI270304v1");
-        m.insert("KI270303.1", "chrUn_KI270303v1");
-        m.insert("KI270305.1", "chrUn_KI270305v1");
-        m.insert("KI270322.1", "chrUn_KI270322v1");
-        m.insert("KI270320.1", "chrUn_KI270320v1");
-        m.insert("KI270310.1", "chrUn_KI270310v1");
-        m.insert("KI270316.1", "chrUn_KI270316v1");
-        m.insert("KI270315.1", "chrUn_KI270315v1");
-        m.insert("KI270312.1", "chrUn_KI270312v1");
-        m.insert("KI270311.1", "chrUn_KI270311v1");
-        m.insert("KI270317.1", "chrUn_KI270317v1");
-        m.insert("KI270412.1", "chrUn_KI270412v1");
-        m.insert("KI270411.1", "chrUn_KI270411v1");
-        m.insert("KI270414.1", "chrUn_KI270414v1");
-        m.insert("KI270419.1", "chrUn_KI270419v1");
-        m.insert("KI270418.1", "chrUn_KI270418v1");
-        m.insert("KI270420.1", "chrUn_KI270420v1");
-        m.insert("KI270424.1", "chrUn_KI270424v1");
-        m.insert("KI270417.1", "chrUn_KI270417v1");
-        m.insert("KI270422.1", "chrUn_KI270422v1");
-        m.insert("KI270423.1", "chrUn_KI270423v1");
-        m.insert("KI270425.1", "chrUn_KI270425v1");
-        m.insert("KI270429.1", "chrUn_KI270429v1");
-        m.insert("KI270442.1", "chrUn_KI270442v1");
-        m.insert("KI270466.1", "chrUn_KI270466v1");
-        m.insert("KI270465.1", "chrUn_KI270465v1");
-        m.insert("KI270467.1", "chrUn_KI270467v1");
-        m.insert("KI270435.1", "chrUn_KI270435v1");
-        m.insert("KI270438.1", "chrUn_KI270438v1");
-        m.insert("KI270468.1", "chrUn_KI270468v1");
-        m.insert("KI270510.1", "chrUn_KI270510v1");
-        m.insert("KI270509.1", "chrUn_KI270509v1");
-        m.insert("KI270518.1", "chrUn_KI270518v1");
-        m.insert("KI270508.1", "chrUn_KI270508v1");
-        m.insert("KI270516.1", "chrUn_KI270516v1");
-        m.insert("KI270512.1", "chrUn_KI270512v1");
-        m.insert("KI270519.1", "chrUn_KI270519v1");
-        m.insert("KI270522.1", "chrUn_KI270522v1");
-        m.insert("KI270511.1", "chrUn_KI270511v1");
-        m.insert("KI270515.1", "chrUn_KI270515v1");
-        m.insert("KI270507.1", "chrUn_KI270507v1");
-        m.insert("KI270517.1", "chrUn_KI270517v1");
-        m.insert("KI270529.1", "chrUn_KI270529v1");
-        m.insert("KI270528.1", "chrUn_KI270528v1");
-        m.insert("KI270530.1", "chrUn_KI270530v1");
-        m.insert("KI270539.1", "chrUn_KI270539v1");
-        m.insert("KI270538.1", "chrUn_KI270538v1");
-        m.insert("KI270544.1", "chrUn_KI270544v1");
-        m.insert("KI270548.1", "chrUn_KI270548v1");
-        m.insert("KI270583.1", "chrUn_KI270583v1");
-        m.insert("KI270587.1", "chrUn_KI270587v1");
-        m.insert("KI270580.1", "chrUn_KI270580v1");
-        m.insert("KI270581.1", "chrUn_KI270581v1");
-        m.insert("KI270579.1", "chrUn_KI270579v1");
-        m.insert("KI270589.1", "chrUn_KI270589v1");
-        m.insert("KI270590.1", "chrUn_KI270590v1");
-        m.insert("KI270584.1", "chrUn_KI270584v1");
-        m.insert("KI270582.1", "chrUn_KI270582v1");
-        m.insert("KI270588.1", "chrUn_KI270588v1");
-        m.insert("KI270593.1", "chrUn_KI270593v1");
-        m.insert("KI270591.1", "chrUn_KI270591v1");
-        m.insert("KI270330.1", "chrUn_KI270330v1");
-        m.insert("KI270329.1", "chrUn_KI270329v1");
-        m.insert("KI270334.1", "chrUn_KI270334v1");
-        m.insert("KI270333.1", "chrUn_KI270333v1");
-        m.insert("KI270335.1", "chrUn_KI270335v1");
-        m.insert("KI270338.1", "chrUn_KI270338v1");
-        m.insert("KI270340.1", "chrUn_KI270340v1");
-        m.insert("KI270336.1", "chrUn_KI270336v1");
-        m.insert("KI270337.1", "chrUn_KI270337v1");
-        m.insert("KI270363.1", "chrUn_KI270363v1");
-        m.insert("KI270364.1", "chrUn_KI270364v1");
-        m.insert("KI270362.1", "chrUn_KI270362v1");
-        m.insert("KI270366.1", "chrUn_KI270366v1");
-        m.insert("KI270378.1", "chrUn_KI270378v1");
-        m.insert("KI270379.1", "chrUn_KI270379v1");
-        m.insert("KI270389.1", "chrUn_KI270389v1");
-        m.insert("KI270390.1", "chrUn_KI270390v1");
-        m.insert("KI270387.1", "chrUn_KI270387v1");
-        m.insert("KI270395.1", "chrUn_KI270395v1");
-        m.insert("KI270396.1", "chrUn_KI270396v1");
-        m.insert("KI270388.1", "chrUn_KI270388v1");
-        m.insert("KI270394.1", "chrUn_KI270394v1");
-        m.insert("KI270386.1", "chrUn_KI270386v1");
-        m.insert("KI270391.1", "chrUn_KI270391v1");
-        m.insert("KI270383.1", "chrUn_KI270383v1");
-        m.insert("KI270393.1", "chrUn_KI270393v1");
-        m.insert("KI270384.1", "chrUn_KI270384v1");
-        m.insert("KI270392.1", "chrUn_KI270392v1");
-        m.insert("KI270381.1", "chrUn_KI270381v1");
-        m.insert("KI270385.1", "chrUn_KI270385v1");
-        m.insert("KI270382.1", "chrUn_KI270382v1");
-        m.insert("KI270376.1", "chrUn_KI270376v1");
-        m.insert("KI270374.1", "chrUn_KI270374v1");
-        m.insert("KI270372.1", "chrUn_KI270372v1");
-        m.insert("KI270373.1", "chrUn_KI270373v1");
-        m.insert("KI270375.1", "chrUn_KI270375v1");
-        m.insert("KI270371.1", "chrUn_KI270371v1");
-        m.insert("KI270448.1", "chrUn_KI270448v1");
-        m.insert("KI270521.1", "chrUn_KI270521v1");
-        m.insert("GL000195.1", "chrUn_GL000195v1");
-        m.insert("GL000219.1", "chrUn_GL000219v1");
-        m.insert("GL000220.1", "chrUn_GL000220v1");
-        m.insert("GL000224.1", "chrUn_GL000224v1");
-        m.insert("KI270741.1", "chrUn_KI270741v1");
-        m.insert("GL000226.1", "chrUn_GL000226v1");
-        m.insert("GL000213.1", "chrUn_GL000213v1");
-        m.insert("KI270743.1", "chrUn_KI270743v1");
-        m.insert("KI270744.1", "chrUn_KI270744v1");
-        m.insert("KI270745.1", "chrUn_KI270745v1");
-        m.insert("KI270746.1", "chrUn_KI270746v1");
-        m.insert("KI270747.1", "chrUn_KI270747v1");
-        m.insert("KI270748.1", "chrUn_KI270748v1");
-        m.insert("KI270749.1", "chrUn_KI270749v1");
-        m.insert("KI270750.1", "chrUn_KI270750v1");
-        m.insert("KI270751.1", "chrUn_KI270751v1");
-        m.insert("KI270752.1", "chrUn_KI270752v1");
-        m.insert("KI270753.1", "chrUn_KI270753v1");
-        m.insert("KI270754.1", "chrUn_KI270754v1");
-        m.insert("KI270755.1", "chrUn_KI270755v1");
-        m.insert("KI270756.1", "chrUn_KI270756v1");
-        m.insert("KI270757.1", "chrUn_KI270757v1");
-        m.insert("GL000214.1", "chrUn_GL000214v1");
-        m.insert("KI270742.1", "chrUn_KI270742v1");
-        m.insert("GL000216.2", "chrUn_GL000216v2");
-        m.insert("GL000218.1", "chrUn_GL000218v1");
-        m.insert("KI270740.1", "chrY_KI270740v1_random");
-        m
-    };
-}
+#[cfg(feature = "serve")]
+mod serve;
 
 #[derive(Parser)]
 #[command(name = "SPDI")]
 #[command(author = "Ryangguk Kim <rkim@oakbioinformatics.com>")]
-#[command(version = "0.1.0")]
+#[command(version)]
 #[command(about = "SPDI: SPDI format converter")]
 #[command(
     after_help = "<variant> is in the form \"chrom:position:reference base:alternate base\" without quotation marks.\nFor example,\n\"chr1:398239:A:C\" for SNV\n\"chr1:26748347:GAC:TA\" for MNV\n\"chr1:2378233:-:A\" for insertion\n\"chr1:72378854:T:-\" for deletion\n\nOutput is an SPDI format string. For example, \"chr1:8734834:GTGT:GT\"\n\nSPDI paper: https://doi.org/10.1093%2Fbioinformatics%2Fbtz856\n\nCopyright 2024 Ryangguk Kim @ Oak Bioinformatics, LLC. Licensed under AGPL-3 and commercial license terms"
 )]
 struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Convert variants to SPDI format
+    Convert(ConvertArgs),
+    /// Add SPDI annotations to a VCF file
+    AnnotateVcf(AnnotateVcfArgs),
+    /// Check REF bases and alleles of a VCF file against the reference
+    Validate(ValidateArgs),
+    /// Convert SPDIs to VCF records
+    ToVcf(ToVcfArgs),
+    /// Check whether two variants have the same SPDI
+    Compare(CompareArgs),
+    /// Print summary statistics of converting a VCF file as JSON
+    Stats(StatsArgs),
+    /// Serve conversions over HTTP with the reference loaded once
+    #[cfg(feature = "serve")]
+    Serve(serve::ServeArgs),
+}
+
+#[derive(Args)]
+struct ReferenceArgs {
     #[arg(id = "twobit_path")]
     #[arg(
         help = "Path to a 2bit file. What is a 2bit file? See https://genome.ucsc.edu/goldenPath/help/twoBit.html. 2bit files can be downloaded at for example https://hgdownload.cse.ucsc.edu/goldenpath/hg38/bigZips/"
     )]
     #[arg(short = 't')]
     twobit_path: String,
-    #[arg(help = "Input variant")]
-    #[arg(id = "variant")]
-    #[arg(short = 'v')]
-    #[arg(default_value = "")]
-    variant: String,
+    #[arg(help = "Contig alias file, for example UCSC chromAlias.txt. Each line has names of one sequence separated by tabs or spaces.")]
+    #[arg(id = "contig_aliases")]
+    #[arg(long = "contig-aliases")]
+    contig_aliases: Option<String>,
+    #[arg(help = "Circular sequences such as chrM or plasmids, separated by commas. Indels on them are normalized across the origin.")]
+    #[arg(id = "circular")]
+    #[arg(long = "circular")]
+    #[arg(value_delimiter = ',')]
+    circular: Vec<String>,
+}
+
+#[derive(Args)]
+struct ContigCheckArgs {
+    #[arg(help = "What to do when ##contig lines of the VCF file do not match the reference")]
+    #[arg(id = "contig_check")]
+    #[arg(long = "contig-check")]
+    #[arg(value_enum)]
+    #[arg(default_value_t = ContigCheck::Warn)]
+    contig_check: ContigCheck,
+    #[arg(help = "Assembly name of the reference, compared with the assembly attribute of ##contig lines (e.g. GRCh38)")]
+    #[arg(id = "reference_assembly")]
+    #[arg(long = "reference-assembly")]
+    reference_assembly: Option<String>,
+}
+
+#[derive(Args)]
+struct ConvertArgs {
+    #[command(flatten)]
+    reference: ReferenceArgs,
+    #[arg(help = "Input variants")]
+    #[arg(id = "variants")]
+    #[arg(required_unless_present = "input")]
+    variants: Vec<String>,
+    #[arg(help = "File with one variant per line, or - for standard input. Each line is written with its SPDI in an added column.")]
+    #[arg(id = "input")]
+    #[arg(short = 'i')]
+    #[arg(long = "input")]
+    #[arg(conflicts_with = "variants")]
+    input: Option<String>,
+    #[arg(help = "Read tab-separated lines with chrom, pos, ref and alt in these 1-based columns, for example 1,2,4,5. Without it, lines are chrom:pos:ref:alt.")]
+    #[arg(id = "columns")]
+    #[arg(long = "columns")]
+    #[arg(value_delimiter = ',')]
+    #[arg(requires = "input")]
+    columns: Vec<usize>,
+    #[arg(help = "The first line of the input is a header. SPDI is added to it as a column name.")]
+    #[arg(id = "header")]
+    #[arg(long = "header")]
+    #[arg(requires = "input")]
+    header: bool,
+    #[arg(help = "Output format")]
+    #[arg(id = "format")]
+    #[arg(long = "format")]
+    #[arg(value_enum)]
+    #[arg(default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+}
+
+#[derive(Args)]
+struct AnnotateVcfArgs {
+    #[command(flatten)]
+    reference: ReferenceArgs,
+    #[arg(help = "Variant VCF file")]
+    #[arg(id = "input_file")]
+    #[arg(short = 'f')]
+    input_file: String,
+    #[arg(help = "INFO key for SPDI output of a VCF file")]
+    #[arg(id = "info_key")]
+    #[arg(long = "info-key")]
+    #[arg(default_value = "OV_SPDI_IDS")]
+    info_key: String,
+    #[arg(help = "Description of the INFO key in the VCF header")]
+    #[arg(id = "info_description")]
+    #[arg(long = "info-description")]
+    #[arg(default_value = "SPDI notation of each alternate allele")]
+    info_description: String,
+    #[arg(help = "How to write SPDIs into the ID column of a VCF file")]
+    #[arg(id = "id_column")]
+    #[arg(long = "id-column")]
+    #[arg(value_enum)]
+    #[arg(default_value_t = IdColumn::Off)]
+    id_column: IdColumn,
+    #[arg(help = "Also write normalized position, reference and alternate bases as <info_key>_POS, <info_key>_REF and <info_key>_ALT")]
+    #[arg(id = "split_fields")]
+    #[arg(long = "split-fields")]
+    split_fields: bool,
+    #[arg(help = "Also write the class of each alternate allele (identity, snv, mnv, insertion, deletion or delins) as <info_key>_CLASS, and 1 for indels which can be placed at more than one position or 0 as <info_key>_SHIFTABLE")]
+    #[arg(id = "class_fields")]
+    #[arg(long = "class-fields")]
+    class_fields: bool,
+    #[arg(help = "Also write the tandem repeat which each shiftable indel changes as <info_key>_MOTIF, <info_key>_PERIOD, <info_key>_REF_COPIES and <info_key>_ALT_COPIES")]
+    #[arg(id = "repeat_fields")]
+    #[arg(long = "repeat-fields")]
+    repeat_fields: bool,
+    #[arg(help = "Also write how many bases the SPDI of each alternate allele extends to the left and right of the allele without its common prefix and suffix as <info_key>_LEFT_SHIFT and <info_key>_RIGHT_SHIFT")]
+    #[arg(id = "shift_fields")]
+    #[arg(long = "shift-fields")]
+    shift_fields: bool,
+    #[arg(help = "What to do when the input VCF file already has SPDI annotations")]
+    #[arg(id = "existing")]
+    #[arg(long = "existing")]
+    #[arg(value_enum)]
+    #[arg(default_value_t = ExistingPolicy::Overwrite)]
+    existing: ExistingPolicy,
+    #[arg(help = "Write the length and MD5 checksum of each reference sequence into the VCF header. This reads the whole reference before annotating.")]
+    #[arg(id = "reference_checksums")]
+    #[arg(long = "reference-checksums")]
+    reference_checksums: bool,
+    #[command(flatten)]
+    contig_check: ContigCheckArgs,
+    #[arg(help = "Sequence names to use in SPDIs of a VCF file. The ucsc, ensembl, genbank and refseq styles need a GRCh38 reference.")]
+    #[arg(id = "contig_naming")]
+    #[arg(long = "contig-naming")]
+    #[arg(value_enum)]
+    #[arg(default_value_t = ContigNamingArg::Reference)]
+    contig_naming: ContigNamingArg,
+    #[arg(help = "Treat alleles of records whose REF bases do not match the reference as errors")]
+    #[arg(id = "check_reference")]
+    #[arg(long = "check-reference")]
+    check_reference: bool,
+    #[arg(help = "File to write alleles of a VCF file which could not be converted, one per line")]
+    #[arg(id = "reject_file")]
+    #[arg(long = "reject-file")]
+    reject_file: Option<String>,
+    #[arg(help = "Format of the reject file")]
+    #[arg(id = "reject_format")]
+    #[arg(long = "reject-format")]
+    #[arg(value_enum)]
+    #[arg(default_value_t = RejectFormat::Tsv)]
+    reject_format: RejectFormat,
+    #[arg(help = "File to write summary statistics of a VCF file run as JSON. Use - for standard error.")]
+    #[arg(id = "summary_file")]
+    #[arg(long = "summary-file")]
+    summary_file: Option<String>,
+    #[arg(help = "Stop at the first allele of a VCF file which cannot be converted. Alleles on contigs which are not in the reference count as errors. Spanning deletions (*), symbolic alleles and breakends are written as . and do not.")]
+    #[arg(id = "strict")]
+    #[arg(long = "strict")]
+    #[arg(conflicts_with_all = ["lenient", "max_errors"])]
+    strict: bool,
+    #[arg(help = "Write . for alleles of a VCF file which cannot be converted and continue. This is the default.")]
+    #[arg(id = "lenient")]
+    #[arg(long = "lenient")]
+    #[arg(conflicts_with = "max_errors")]
+    lenient: bool,
+    #[arg(help = "Stop when more than this number of alleles of a VCF file cannot be converted")]
+    #[arg(id = "max_errors")]
+    #[arg(long = "max-errors")]
+    max_errors: Option<usize>,
+}
+
+#[derive(Args)]
+struct ValidateArgs {
+    #[command(flatten)]
+    reference: ReferenceArgs,
+    #[arg(help = "Variant VCF file")]
+    #[arg(id = "input_file")]
+    #[arg(short = 'f')]
+    input_file: String,
+    #[command(flatten)]
+    contig_check: ContigCheckArgs,
+    #[arg(help = "Format of the problems written to standard output")]
+    #[arg(id = "format")]
+    #[arg(long = "format")]
+    #[arg(value_enum)]
+    #[arg(default_value_t = RejectFormat::Tsv)]
+    format: RejectFormat,
+}
+
+#[derive(Args)]
+struct ToVcfArgs {
+    #[arg(id = "twobit_path")]
+    #[arg(help = "Path to a 2bit file. Needed for SPDIs with an empty deleted or inserted sequence, which get a padding base.")]
+    #[arg(short = 't')]
+    twobit_path: Option<String>,
+    #[arg(help = "Contig alias file, for example UCSC chromAlias.txt")]
+    #[arg(id = "contig_aliases")]
+    #[arg(long = "contig-aliases")]
+    #[arg(requires = "twobit_path")]
+    contig_aliases: Option<String>,
+    #[arg(help = "SPDIs such as chr1:8734834:GTGT:GT")]
+    #[arg(id = "spdis")]
+    #[arg(required = true)]
+    spdis: Vec<String>,
+}
+
+#[derive(Args)]
+struct CompareArgs {
+    #[command(flatten)]
+    reference: ReferenceArgs,
+    #[arg(help = "First variant")]
+    #[arg(id = "variant1")]
+    variant1: String,
+    #[arg(help = "Second variant")]
+    #[arg(id = "variant2")]
+    variant2: String,
+}
+
+#[derive(Args)]
+struct StatsArgs {
+    #[command(flatten)]
+    reference: ReferenceArgs,
     #[arg(help = "Variant VCF file")]
     #[arg(id = "input_file")]
     #[arg(short = 'f')]
-    #[arg(default_value = "")]
     input_file: String,
+    #[command(flatten)]
+    contig_check: ContigCheckArgs,
+}
+
+/// Exit codes. 2 is also used by clap for invalid arguments.
+const EXIT_USAGE: i32 = 2;
+/// An input, output, reject, summary or alias file could not be read or written.
+const EXIT_IO: i32 = 3;
+/// The reference could not be opened or does not match the input.
+const EXIT_REFERENCE: i32 = 4;
+/// The input variant or VCF file is malformed or already annotated.
+const EXIT_PARSE: i32 = 5;
+/// Variants could not be converted, with `convert`, `--strict` or `--max-errors`.
+const EXIT_CONVERSION: i32 = 6;
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum ContigNamingArg {
+    /// Names in the reference file
+    Reference,
+    /// Names in the input VCF file
+    Input,
+    /// chr1, chrM, chr14_GL000009v2_random
+    Ucsc,
+    /// 1, MT, GL000009.2
+    Ensembl,
+    /// CM000663.2, J01415.2, GL000009.2
+    Genbank,
+    /// NC_000001.11, NC_012920.1
+    Refseq,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum ContigCheck {
+    /// Do not compare ##contig lines with the reference.
+    Off,
+    /// Print a warning for each mismatch.
+    Warn,
+    /// Stop with an error at the first mismatch.
+    Fail,
+}
+
+/// Suffixes of the INFO keys derived from the INFO key, as in
+/// `OV_SPDI_IDS_POS`.
+const INFO_KEY_SUFFIXES: [&str; 11] = [
+    "POS",
+    "REF",
+    "ALT",
+    "CLASS",
+    "SHIFTABLE",
+    "MOTIF",
+    "PERIOD",
+    "REF_COPIES",
+    "ALT_COPIES",
+    "LEFT_SHIFT",
+    "RIGHT_SHIFT",
+];
+
+/// Header lines describing how SPDIs were produced. Lines from an earlier run
+/// are handled like the SPDI INFO header lines.
+const PROVENANCE_IDS: [&str; 4] = [
+    "##spdiVersion",
+    "##spdiCommand",
+    "##spdiReference",
+    "##spdiReferenceSequence",
+];
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum ExistingPolicy {
+    /// Replace existing SPDI header lines and INFO fields.
+    Overwrite,
+    /// Keep existing SPDI annotations and annotate only records without them.
+    Skip,
+    /// Stop with an error.
+    Fail,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum IdColumn {
+    /// Leave the ID column as it is.
+    Off,
+    /// Replace the ID column with SPDIs.
+    Replace,
+    /// Fill an empty (`.`) ID column with SPDIs or append them to existing IDs.
+    Append,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum OutputFormat {
+    /// SPDIs, or input lines with their SPDI in an added column
+    Text,
+    /// A JSON array with an object for each variant
+    Json,
+    /// A JSON object for each variant on its own line
+    Ndjson,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum RejectFormat {
+    /// Tab-separated CHROM, POS, REF, ALT, KIND and MESSAGE columns with a header line
+    Tsv,
+    /// One JSON object per line with chrom, pos, ref, alt, kind and message
+    Jsonl,
+}
+
+/// Writes alleles which could not be converted, with the kind of the error
+/// from `spdi::error::Error` so that failures can be counted by reason.
+struct RejectWriter {
+    writer: Box<dyn Write>,
+    format: RejectFormat,
+}
+
+impl RejectWriter {
+    fn new(mut writer: Box<dyn Write>, format: RejectFormat) -> std::io::Result<RejectWriter> {
+        if format == RejectFormat::Tsv {
+            writeln!(writer, "#CHROM\tPOS\tREF\tALT\tKIND\tMESSAGE")?;
+        }
+        Ok(RejectWriter { writer, format })
+    }
+
+    fn create(path: &str, format: RejectFormat) -> std::io::Result<RejectWriter> {
+        RejectWriter::new(Box::new(BufWriter::new(std::fs::File::create(path)?)), format)
+    }
+
+    fn write(
+        &mut self,
+        chrom: &str,
+        pos: &str,
+        ref_base: &str,
+        alt_base: &str,
+        kind: &str,
+        message: &str,
+    ) -> std::io::Result<()> {
+        match self.format {
+            RejectFormat::Tsv => writeln!(
+                self.writer,
+                "{}\t{}\t{}\t{}\t{}\t{}",
+                chrom,
+                pos,
+                ref_base,
+                alt_base,
+                kind,
+                message.replace(['\t', '\n'], " ")
+            ),
+            RejectFormat::Jsonl => writeln!(
+                self.writer,
+                "{}",
+                serde_json::json!({
+                    "chrom": chrom,
+                    "pos": pos.parse::<usize>().ok(),
+                    "ref": ref_base,
+                    "alt": alt_base,
+                    "kind": kind,
+                    "message": message,
+                })
+            ),
+        }
+    }
+}
+
+/// Writes a row to the reject file if one was given.
+fn write_reject(
+    rejects: &mut Option<RejectWriter>,
+    words: &[&str],
+    alt_base: &str,
+    kind: &str,
+    message: &str,
+) {
+    if let Some(rejects) = rejects.as_mut() {
+        if let Err(e) = rejects.write(words[0], words[1], words[3], alt_base, kind, message) {
+            eprintln!("Cannot write to the reject file: {}", e);
+            std::process::exit(EXIT_IO);
+        }
+    }
+}
+
+/// Returns true if the ALT allele is not a sequence which can be converted:
+/// a spanning deletion `*`, a missing allele `.`, a symbolic allele like
+/// `<DEL>` or a breakend.
+fn is_unsupported_allele(alt_base: &str) -> bool {
+    alt_base == "*"
+        || alt_base == "."
+        || alt_base.starts_with('<')
+        || alt_base.contains('[')
+        || alt_base.contains(']')
+}
+
+/// Counts of a VCF file run.
+#[derive(Default)]
+struct RunSummary {
+    records: usize,
+    skipped_records: usize,
+    alleles: usize,
+    classes: BTreeMap<&'static str, usize>,
+    unsupported_contigs: BTreeMap<String, usize>,
+    unsupported_alleles: usize,
+    errors: BTreeMap<String, usize>,
+    shifted_alleles: usize,
+    total_expansion: usize,
+    max_expansion: usize,
+    max_expansion_allele: Option<serde_json::Value>,
+    aborted: bool,
+}
+
+impl RunSummary {
+    /// Adds a converted allele. The expansion is the number of reference bases
+    /// which the SPDI has in addition to the input allele without its common
+    /// prefix and suffix. An allele is shifted if its SPDI is not that allele.
+    fn add_allele(
+        &mut self,
+        words: &[&str],
+        pos: usize,
+        alt_base: &str,
+        spdi_string: &str,
+        change: &AlleleChange,
+    ) {
+        *self.classes.entry(change.class.as_str()).or_insert(0) += 1;
+        let expansion = change.shifts.grown_left + change.shifts.grown_right;
+        if expansion > 0 {
+            self.shifted_alleles += 1;
+        }
+        self.total_expansion += expansion;
+        if expansion > self.max_expansion {
+            self.max_expansion = expansion;
+            self.max_expansion_allele = Some(serde_json::json!({
+                "chrom": words[0],
+                "pos": pos,
+                "ref": words[3],
+                "alt": alt_base,
+                "spdi": spdi_string,
+            }));
+        }
+    }
+
+    fn add_error(&mut self, kind: &str) {
+        *self.errors.entry(kind.to_string()).or_insert(0) += 1;
+    }
+
+    fn has_too_many_errors(&self, max_errors: Option<usize>) -> bool {
+        match max_errors {
+            Some(max) => self.errors.values().sum::<usize>() > max,
+            None => false,
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "records": self.records,
+            "skipped_records": self.skipped_records,
+            "alleles": self.alleles,
+            "classes": self.classes,
+            "unsupported_contigs": self.unsupported_contigs,
+            "unsupported_alleles": self.unsupported_alleles,
+            "errors": self.errors,
+            "shifted_alleles": self.shifted_alleles,
+            "total_expansion": self.total_expansion,
+            "max_expansion": self.max_expansion,
+            "max_expansion_allele": self.max_expansion_allele,
+            "aborted": self.aborted,
+        })
+    }
+}
+
+struct AnnotateOptions {
+    info_key: String,
+    info_description: String,
+    id_column: IdColumn,
+    split_fields: bool,
+    class_fields: bool,
+    repeat_fields: bool,
+    shift_fields: bool,
+    existing: ExistingPolicy,
+    reference_path: String,
+    reference_checksums: bool,
+    command: String,
+    contig_check: ContigCheck,
+    reference_assembly: Option<String>,
+    contig_naming: ContigNamingArg,
+    check_reference: bool,
+    max_errors: Option<usize>,
+}
+
+impl AnnotateOptions {
+    /// Options of VCF subcommands which do not write annotations, and so do
+    /// not compute reference checksums for provenance header lines.
+    fn new(reference: &ReferenceArgs, contig_check: &ContigCheckArgs) -> AnnotateOptions {
+        let reference_path = match std::fs::canonicalize(&reference.twobit_path) {
+            Ok(v) => v.to_string_lossy().to_string(),
+            Err(_) => reference.twobit_path.clone(),
+        };
+        AnnotateOptions {
+            info_key: "OV_SPDI_IDS".to_string(),
+            info_description: "SPDI notation of each alternate allele".to_string(),
+            id_column: IdColumn::Off,
+            split_fields: false,
+            class_fields: false,
+            repeat_fields: false,
+            shift_fields: false,
+            existing: ExistingPolicy::Overwrite,
+            reference_path,
+            reference_checksums: false,
+            command: std::env::args().collect::<Vec<String>>().join(" "),
+            contig_check: contig_check.contig_check,
+            reference_assembly: contig_check.reference_assembly.clone(),
+            contig_naming: ContigNamingArg::Reference,
+            check_reference: false,
+            max_errors: None,
+        }
+    }
+
+    fn from_args(args: &AnnotateVcfArgs) -> AnnotateOptions {
+        AnnotateOptions {
+            info_key: args.info_key.clone(),
+            info_description: args.info_description.clone(),
+            id_column: args.id_column,
+            split_fields: args.split_fields,
+            class_fields: args.class_fields,
+            repeat_fields: args.repeat_fields,
+            shift_fields: args.shift_fields,
+            existing: args.existing,
+            reference_checksums: args.reference_checksums,
+            contig_naming: args.contig_naming,
+            check_reference: args.check_reference,
+            max_errors: match args.strict {
+                true => Some(0),
+                false => args.max_errors,
+            },
+            ..AnnotateOptions::new(&args.reference, &args.contig_check)
+        }
+    }
+
+    /// Returns the sequence name to use in SPDIs. GRCh38 sequences without a
+    /// name in the requested style keep their name in the reference.
+    fn get_spdi_chrom(
+        &self,
+        input_chrom: &str,
+        twobit_chrom: &str,
+        resolver: &ContigResolver,
+    ) -> String {
+        let naming = match self.contig_naming {
+            ContigNamingArg::Reference => return twobit_chrom.to_string(),
+            ContigNamingArg::Input => return input_chrom.to_string(),
+            ContigNamingArg::Ucsc => ContigNaming::Ucsc,
+            ContigNamingArg::Ensembl => ContigNaming::Ensembl,
+            ContigNamingArg::Genbank => ContigNaming::GenBank,
+            ContigNamingArg::Refseq => ContigNaming::RefSeq,
+        };
+        // GRCh38 names of the mitochondrion are those of the rCRS.
+        if let Some((mito_name, mito)) = resolver.mito() {
+            if mito_name == twobit_chrom {
+                return match (mito, naming) {
+                    (MitoSequence::Rcrs, _) => get_contig_name("chrM", naming),
+                    (MitoSequence::Yoruba, ContigNaming::RefSeq) => Some("NC_001807.4".to_string()),
+                    _ => None,
+                }
+                .unwrap_or_else(|| twobit_chrom.to_string());
+            }
+        }
+        get_contig_name(twobit_chrom, naming).unwrap_or_else(|| twobit_chrom.to_string())
+    }
+
+    /// Provenance header lines paired with their IDs in `PROVENANCE_IDS`.
+    /// Reference sequences are only listed with `--reference-checksums`, as
+    /// their checksums read the whole reference.
+    fn provenance_lines(&self, spdi: &SPDI) -> Vec<(String, String)> {
+        let mut lines: Vec<(String, String)> = vec![
+            (
+                "##spdiVersion".to_string(),
+                format!("##spdiVersion={}", env!("CARGO_PKG_VERSION")),
+            ),
+            (
+                "##spdiCommand".to_string(),
+                format!("##spdiCommand={}", self.command),
+            ),
+            (
+                "##spdiReference".to_string(),
+                format!("##spdiReference=file://{}", self.reference_path),
+            ),
+        ];
+        if !self.reference_checksums {
+            return lines;
+        }
+        for name in spdi.sequence_names() {
+            let length = spdi.sequence_length(name).unwrap_or(0);
+            let line = match spdi.sequence_md5(name) {
+                Ok(md5) => format!(
+                    "##spdiReferenceSequence=<ID={},length={},md5={}>",
+                    name, length, md5
+                ),
+                Err(_) => format!("##spdiReferenceSequence=<ID={},length={}>", name, length),
+            };
+            lines.push(("##spdiReferenceSequence".to_string(), line));
+        }
+        lines
+    }
+
+    /// INFO keys written by the annotator paired with their header lines.
+    fn header_lines(&self) -> Vec<(String, String)> {
+        let mut lines: Vec<(String, String)> = vec![(
+            self.info_key.clone(),
+            format!(
+                "##INFO=<ID={},Number=A,Type=String,Description=\"{}\">",
+                self.info_key, self.info_description
+            ),
+        )];
+        let mut fields: Vec<(&str, &str, &str)> = Vec::new();
+        if self.split_fields {
+            fields.extend([
+                ("POS", "Integer", "Normalized position of each alternate allele"),
+                ("REF", "String", "Normalized reference bases of each alternate allele"),
+                ("ALT", "String", "Normalized alternate bases of each alternate allele"),
+            ]);
+        }
+        if self.class_fields {
+            fields.extend([
+                ("CLASS", "String", "Class of each alternate allele: identity, snv, mnv, insertion, deletion or delins"),
+                ("SHIFTABLE", "Integer", "1 if the indel of each alternate allele can be placed at more than one position, otherwise 0"),
+            ]);
+        }
+        if self.repeat_fields {
+            fields.extend([
+                ("MOTIF", "String", "Shortest repeated unit of the tandem repeat which each alternate allele changes"),
+                ("PERIOD", "Integer", "Length of the repeated unit of each alternate allele"),
+                ("REF_COPIES", "Integer", "Whole copies of the repeated unit in the normalized reference bases of each alternate allele"),
+                ("ALT_COPIES", "Integer", "Whole copies of the repeated unit in the normalized alternate bases of each alternate allele"),
+            ]);
+        }
+        if self.shift_fields {
+            fields.extend([
+                ("LEFT_SHIFT", "Integer", "Bases which the normalized allele extends to the left of each trimmed alternate allele"),
+                ("RIGHT_SHIFT", "Integer", "Bases which the normalized allele extends to the right of each trimmed alternate allele"),
+            ]);
+        }
+        for (suffix, info_type, description) in fields {
+            let key = format!("{}_{}", self.info_key, suffix);
+            let line = format!(
+                "##INFO=<ID={},Number=A,Type={},Description=\"{}\">",
+                key, info_type, description
+            );
+            lines.push((key, line));
+        }
+        lines
+    }
+
+    /// Returns true for the INFO key and the keys derived from it, whether or
+    /// not this run writes them, so that fields of an earlier run with other
+    /// options are not left behind.
+    fn is_own_key(&self, key: &str) -> bool {
+        match key.strip_prefix(self.info_key.as_str()) {
+            Some("") => true,
+            Some(rest) => rest
+                .strip_prefix('_')
+                .is_some_and(|suffix| INFO_KEY_SUFFIXES.contains(&suffix)),
+            None => false,
+        }
+    }
+}
+
+/// Returns the values of the repeat INFO fields of an allele, or `.` for
+/// alleles which do not change a repeat.
+fn get_repeat_fields(repeat: Option<&TandemRepeat>) -> [String; 4] {
+    match repeat {
+        Some(v) => [
+            v.motif.clone(),
+            v.period().to_string(),
+            v.ref_copies.to_string(),
+            v.alt_copies.to_string(),
+        ],
+        None => [".", ".", ".", "."].map(String::from),
+    }
+}
+
+/// Returns the ID of an `##INFO=<ID=...>` header line.
+fn get_header_info_key(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix("##INFO=<ID=")?;
+    rest.split([',', '>']).next()
+}
+
+/// Returns the attributes of a structured header line such as
+/// `##contig=<ID=1,length=248956422>`. Quotes around values are removed.
+fn get_header_attributes(line: &str) -> Vec<(String, String)> {
+    let body = match line.split_once("=<") {
+        Some((_, v)) => v.strip_suffix('>').unwrap_or(v),
+        None => return Vec::new(),
+    };
+    let mut fields: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    for c in body.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+        .iter()
+        .filter_map(|v| v.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+/// Compares a `##contig` header line with the reference and returns a message
+/// for each mismatch. Contigs which are not in the reference are not reported.
+fn check_contig_line(
+    line: &str,
+    spdi: &SPDI,
+    resolver: &ContigResolver,
+    options: &AnnotateOptions,
+) -> Vec<String> {
+    let mut mismatches: Vec<String> = Vec::new();
+    let attributes = get_header_attributes(line);
+    let get_attribute = |key: &str| {
+        attributes
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    };
+    let id = match get_attribute("ID") {
+        Some(v) => v,
+        None => return mismatches,
+    };
+    let chrom = match resolver.resolve(id) {
+        Some(v) => v,
+        None => return mismatches,
+    };
+    let ref_length = match spdi.sequence_length(&chrom) {
+        Some(v) => v,
+        None => return mismatches,
+    };
+    if let Some(length) = get_attribute("length") {
+        if length.parse::<usize>() != Ok(ref_length) {
+            mismatches.push(format!(
+                "contig [{}] has length {} in the VCF header but [{}] has length {} in the reference",
+                id, length, chrom, ref_length
+            ));
+        }
+    }
+    if let Some(md5) = get_attribute("md5") {
+        if let Ok(ref_md5) = spdi.sequence_md5(&chrom) {
+            if !md5.eq_ignore_ascii_case(&ref_md5) {
+                mismatches.push(format!(
+                    "contig [{}] has md5 {} in the VCF header but [{}] has md5 {} in the reference",
+                    id, md5, chrom, ref_md5
+                ));
+            }
+        }
+    }
+    if let (Some(assembly), Some(ref_assembly)) =
+        (get_attribute("assembly"), options.reference_assembly.as_deref())
+    {
+        if !assembly.eq_ignore_ascii_case(ref_assembly) {
+            mismatches.push(format!(
+                "contig [{}] is from assembly {} but the reference is {}",
+                id, assembly, ref_assembly
+            ));
+        }
+    }
+    mismatches
+}
+
+/// Returns the ID of a provenance header line written by an earlier run.
+fn get_provenance_id(line: &str) -> Option<&str> {
+    let id = line.split('=').next()?;
+    PROVENANCE_IDS.iter().find(|v| **v == id).copied()
 }
 
-fn process_variant(variant: &String, spdi: &mut SPDI) {
-    let words: Vec<&str> = variant.split(':').collect();
+fn get_info_keys(info: &str) -> impl Iterator<Item = &str> {
+    info.split(';')
+        .map(|field| field.split('=').next().unwrap_or(field))
+}
+
+/// Warns when mitochondrial variants are converted on a sequence other than
+/// the rCRS, or cannot be converted because the reference has the other one.
+fn warn_mito(resolver: &ContigResolver, chrom_s: &str, resolved_chrom: Option<&str>) {
+    if MitoSequence::of_sequence(chrom_s, None).is_none() {
+        return;
+    }
+    let (mito_name, ref_mito) = match resolver.mito() {
+        Some(v) => v,
+        None => return,
+    };
+    match (resolved_chrom, ref_mito) {
+        (None, _) => eprintln!(
+            "Warning: [{}] is a different mitochondrial sequence from [{}] in the reference. Its variants were not annotated.",
+            chrom_s, mito_name
+        ),
+        (Some(_), MitoSequence::Yoruba) => eprintln!(
+            "Warning: [{}] in the reference is the Yoruba mitochondrial sequence (hg19 chrM, NC_001807.4). SPDIs of [{}] are not comparable with SPDIs on the rCRS (NC_012920.1).",
+            mito_name, chrom_s
+        ),
+        (Some(_), MitoSequence::Unknown) => eprintln!(
+            "Warning: Could not tell if [{}] in the reference is the rCRS (NC_012920.1) or the Yoruba (NC_001807.4) mitochondrial sequence. SPDIs on the two are not comparable.",
+            mito_name
+        ),
+        _ => {}
+    }
+}
+
+/// An error of the binary with its exit code. The kind is that of
+/// `spdi::error::Error` for errors from the library.
+#[derive(Clone)]
+struct ConvertError {
+    code: i32,
+    kind: &'static str,
+    message: String,
+}
+
+impl ConvertError {
+    fn new(code: i32, kind: &'static str, message: String) -> ConvertError {
+        ConvertError { code, kind, message }
+    }
+}
+
+impl From<spdi::error::Error> for ConvertError {
+    fn from(e: spdi::error::Error) -> Self {
+        ConvertError::new(EXIT_CONVERSION, e.kind(), e.to_string())
+    }
+}
+
+/// Sequence name, position, reference bases and alternate bases of an input.
+type InputVariant<'a> = (&'a str, usize, &'a str, &'a str);
+
+/// Splits a variant or an SPDI into the sequence name, position, reference
+/// bases and alternate bases. Sequence names may have colons.
+fn parse_variant(variant: &str) -> Result<InputVariant<'_>, ConvertError> {
+    let words: Vec<&str> = variant.rsplitn(4, ':').collect();
     if words.len() != 4 {
-        eprintln!("\nWrong input format: [{}]\n", variant);
-        std::process::exit(1);
+        return Err(ConvertError::new(
+            EXIT_PARSE,
+            "InvalidFormat",
+            format!("Wrong input format: [{}]", variant),
+        ));
     }
-    let chrom: &[u8] = words[0].as_bytes();
-    let pos_r = words[1].parse::<usize>();
-    let pos: usize;
-    match pos_r {
-        Ok(v) => pos = v,
-        Err(_) => {
-            eprintln!("\n[{}] is not a valid position.\n", words[1]);
-            std::process::exit(1);
+    let pos = parse_pos(words[2])?;
+    Ok((words[3], pos, words[1], words[0]))
+}
+
+fn parse_pos(pos: &str) -> Result<usize, ConvertError> {
+    pos.parse::<usize>().map_err(|_| {
+        ConvertError::new(
+            EXIT_PARSE,
+            "InvalidPosition",
+            format!("[{}] is not a valid position.", pos),
+        )
+    })
+}
+
+/// Same as `parse_variant` for a line of a batch input. `columns` are the
+/// 1-based columns of chrom, pos, ref and alt in a tab-separated line.
+fn parse_line<'a>(line: &'a str, columns: &[usize]) -> Result<InputVariant<'a>, ConvertError> {
+    if columns.is_empty() {
+        return parse_variant(line.trim());
+    }
+    let words: Vec<&str> = line.split('\t').collect();
+    let fields: Vec<&str> = columns
+        .iter()
+        .map(|column| words.get(column.wrapping_sub(1)).copied())
+        .collect::<Option<Vec<&str>>>()
+        .ok_or_else(|| {
+            ConvertError::new(
+                EXIT_PARSE,
+                "InvalidFormat",
+                format!("Line has {} columns", words.len()),
+            )
+        })?;
+    Ok((fields[0], parse_pos(fields[1])?, fields[2], fields[3]))
+}
+
+/// Class, repeat and normalization shifts of a converted allele.
+struct AlleleChange {
+    class: VariantClass,
+    shiftable: bool,
+    repeat: Option<TandemRepeat>,
+    shifts: NormalizationShifts,
+}
+
+impl AlleleChange {
+    fn new(converted: &ClassifiedSpdi) -> AlleleChange {
+        AlleleChange {
+            class: converted.class,
+            shiftable: converted.shiftable,
+            repeat: converted.repeat.clone(),
+            shifts: converted.shifts,
         }
     }
-    let ref_bases_s: &[u8] = words[2].as_bytes();
-    let alt_bases_s: &[u8] = words[3].as_bytes();
-    let ret = spdi.get_spdi_string(&chrom, pos, &ref_bases_s, &alt_bases_s);
-    match ret {
-        Err(e) => {
-            eprintln!("Error: {:#?}", e);
+}
+
+/// SPDI of an input variant.
+struct ConvertedAllele {
+    chrom: String,
+    pos: usize,
+    ref_base: String,
+    alt_base: String,
+    change: AlleleChange,
+}
+
+impl ConvertedAllele {
+    fn spdi_string(&self) -> String {
+        format!("{}:{}:{}:{}", self.chrom, self.pos, self.ref_base, self.alt_base)
+    }
+}
+
+fn convert_alleles(
+    (chrom_s, pos, ref_base, alt_base): InputVariant,
+    spdi: &SPDI,
+    resolver: &ContigResolver,
+) -> Result<ConvertedAllele, ConvertError> {
+    let new_chrom: String = resolver.resolve(chrom_s).unwrap_or(chrom_s.to_string());
+    let chrom: &[u8] = new_chrom.as_bytes();
+    // - is an empty allele, as in SPDIs.
+    let ref_base = match ref_base {
+        "-" => "",
+        v => v,
+    };
+    let alt_base = match alt_base {
+        "-" => "",
+        v => v,
+    };
+    let converted =
+        spdi.get_classified_spdi_str(chrom, pos, ref_base.as_bytes(), alt_base.as_bytes())?;
+    Ok(ConvertedAllele {
+        change: AlleleChange::new(&converted),
+        chrom: new_chrom,
+        pos: converted.pos,
+        ref_base: converted.ref_bases,
+        alt_base: converted.alt_bases,
+    })
+}
+
+/// Returns the SPDI of a variant in the form chrom:pos:ref:alt.
+fn convert_variant(
+    variant: &str,
+    spdi: &SPDI,
+    resolver: &ContigResolver,
+) -> Result<String, ConvertError> {
+    let input = parse_variant(variant)?;
+    Ok(convert_alleles(input, spdi, resolver)?.spdi_string())
+}
+
+/// Returns the JSON of a conversion for --format json and ndjson.
+fn get_conversion_json(
+    text: &str,
+    line_number: Option<usize>,
+    input: Option<&InputVariant>,
+    result: &Result<ConvertedAllele, ConvertError>,
+) -> serde_json::Value {
+    let mut json = serde_json::json!({});
+    if let Some(line_number) = line_number {
+        json["line"] = serde_json::json!(line_number);
+    }
+    json["input"] = serde_json::json!(text);
+    json["chrom"] = serde_json::json!(input.map(|v| v.0));
+    json["pos"] = serde_json::json!(input.map(|v| v.1));
+    json["ref"] = serde_json::json!(input.map(|v| v.2));
+    json["alt"] = serde_json::json!(input.map(|v| v.3));
+    let converted = result.as_ref().ok();
+    json["spdi"] = serde_json::json!(converted.map(|v| v.spdi_string()));
+    json["seq_id"] = serde_json::json!(converted.map(|v| &v.chrom));
+    json["position"] = serde_json::json!(converted.map(|v| v.pos));
+    json["deleted_sequence"] =
+        serde_json::json!(converted.map(|v| v.ref_base.trim_start_matches('-')));
+    json["inserted_sequence"] =
+        serde_json::json!(converted.map(|v| v.alt_base.trim_start_matches('-')));
+    json["class"] = serde_json::json!(converted.map(|v| v.change.class.as_str()));
+    json["shiftable"] = serde_json::json!(converted.map(|v| v.change.shiftable));
+    json["repeat"] = serde_json::json!(converted.and_then(|v| v.change.repeat.as_ref()).map(|v| {
+        serde_json::json!({
+            "motif": v.motif,
+            "period": v.period(),
+            "ref_copies": v.ref_copies,
+            "alt_copies": v.alt_copies,
+        })
+    }));
+    json["left_shift"] = serde_json::json!(converted.map(|v| v.change.shifts.grown_left));
+    json["right_shift"] = serde_json::json!(converted.map(|v| v.change.shifts.grown_right));
+    json["trimmed_left"] = serde_json::json!(converted.map(|v| v.change.shifts.trimmed_left));
+    json["trimmed_right"] = serde_json::json!(converted.map(|v| v.change.shifts.trimmed_right));
+    json["error"] = match result {
+        Ok(_) => serde_json::Value::Null,
+        Err(e) => serde_json::json!({"kind": e.kind, "message": e.message}),
+    };
+    json
+}
+
+/// Writes the results of `convert` in the format of --format. In text
+/// format, variants are written as SPDIs and lines of a batch input with
+/// their SPDI in an added column, and errors go to standard error.
+struct ConversionWriter<'a> {
+    out: BufWriter<std::io::StdoutLock<'a>>,
+    format: OutputFormat,
+    count: usize,
+    exit_code: i32,
+}
+
+impl<'a> ConversionWriter<'a> {
+    fn new(format: OutputFormat) -> ConversionWriter<'a> {
+        ConversionWriter {
+            out: BufWriter::new(std::io::stdout().lock()),
+            format,
+            count: 0,
+            exit_code: 0,
+        }
+    }
+
+    /// Writes a conversion. `line_number` is given for lines of a batch input.
+    fn write(
+        &mut self,
+        text: &str,
+        line_number: Option<usize>,
+        input: Option<&InputVariant>,
+        result: &Result<ConvertedAllele, ConvertError>,
+    ) {
+        if let Err(e) = result {
+            self.exit_code = e.code;
+        }
+        match self.format {
+            OutputFormat::Text => {
+                let spdi_string = match result {
+                    Ok(v) => v.spdi_string(),
+                    Err(e) => {
+                        match line_number {
+                            Some(n) => eprintln!("Line {}: {}", n, e.message),
+                            None => eprintln!("Error: {}", e.message),
+                        }
+                        ".".to_string()
+                    }
+                };
+                match (line_number, result) {
+                    (Some(_), _) => write_line(&mut self.out, &format!("{}\t{}", text, spdi_string)),
+                    (None, Ok(_)) => write_line(&mut self.out, &spdi_string),
+                    (None, Err(_)) => {}
+                }
+            }
+            OutputFormat::Json => {
+                let json = get_conversion_json(text, line_number, input, result);
+                let separator = if self.count == 0 { "[\n" } else { ",\n" };
+                if let Err(e) = write!(self.out, "{}{}", separator, json) {
+                    eprintln!("Cannot write the output: {}", e);
+                    std::process::exit(EXIT_IO);
+                }
+            }
+            OutputFormat::Ndjson => {
+                let json = get_conversion_json(text, line_number, input, result);
+                write_line(&mut self.out, &json.to_string());
+            }
+        }
+        self.count += 1;
+    }
+
+    /// Writes a line of a batch input which is not a variant.
+    fn write_other(&mut self, line: &str) {
+        if self.format == OutputFormat::Text {
+            write_line(&mut self.out, line);
+        }
+    }
+
+    fn finish(mut self) -> i32 {
+        if self.format == OutputFormat::Json {
+            write_line(&mut self.out, if self.count == 0 { "[]" } else { "\n]" });
         }
-        Ok(v) => {
-            println!("{}", v);
+        if let Err(e) = self.out.flush() {
+            eprintln!("Cannot write the output: {}", e);
+            std::process::exit(EXIT_IO);
         }
+        self.exit_code
     }
 }
 
-fn header_has_sample(line: &String) -> bool {
+/// Returns the VCF position, REF and ALT of an SPDI. An empty deleted or
+/// inserted sequence gets the reference base before it, or after it at the
+/// start of a sequence.
+fn get_vcf_of_spdi(
+    spdi_s: &str,
+    spdi: Option<&SPDI>,
+    resolver: Option<&ContigResolver>,
+) -> Result<(String, usize, String, String), ConvertError> {
+    let (chrom_s, pos, del, ins) = parse_variant(spdi_s)?;
+    let del = match del {
+        "-" => "",
+        v => v,
+    };
+    let ins = match ins {
+        "-" => "",
+        v => v,
+    };
+    for bases in [del, ins] {
+        spdi::util::get_bases_of_string(bases)
+            .map_err(|e| ConvertError::new(EXIT_PARSE, e.kind(), e.to_string()))?;
+    }
+    if !del.is_empty() && !ins.is_empty() {
+        return Ok((chrom_s.to_string(), pos, del.to_string(), ins.to_string()));
+    }
+    if del.is_empty() && ins.is_empty() {
+        return Err(ConvertError::new(
+            EXIT_PARSE,
+            "EmptyVariant",
+            format!("[{}] has neither deleted nor inserted bases.", spdi_s),
+        ));
+    }
+    let (spdi, resolver) = match (spdi, resolver) {
+        (Some(spdi), Some(resolver)) => (spdi, resolver),
+        _ => {
+            return Err(ConvertError::new(
+                EXIT_USAGE,
+                "NoReference",
+                format!("[{}] needs a reference base. Give a 2bit file with -t.", spdi_s),
+            ))
+        }
+    };
+    let chrom = resolver.resolve(chrom_s).unwrap_or(chrom_s.to_string());
+    let anchor_pos = match pos {
+        0 | 1 => pos + del.len(),
+        _ => pos - 1,
+    };
+    let anchor = spdi.get_reference_bases(&chrom, anchor_pos, 1)?;
+    match anchor_pos < pos {
+        true => Ok((chrom_s.to_string(), anchor_pos, anchor.clone() + del, anchor + ins)),
+        false => Ok((chrom_s.to_string(), pos, del.to_string() + &anchor, ins.to_string() + &anchor)),
+    }
+}
+
+fn header_has_sample(line: &str) -> bool {
     let words: Vec<&str> = line.split("\t").collect();
     words.len() > 8
 }
 
-fn process_input_file(input_file: &String, spdi: &mut SPDI) {
-    let f_r = std::fs::File::open(input_file);
-    let f: std::fs::File;
-    match f_r {
+/// Returns INFO with `fields` appended. Existing fields whose keys are owned by
+/// the annotator are dropped so that re-annotating a file does not duplicate
+/// them or keep values of an earlier run.
+fn get_new_info(info: &str, fields: &[(String, String)], options: &AnnotateOptions) -> String {
+    let mut new_info: String = match info {
+        "." | "" => String::new(),
+        _ => info
+            .split(';')
+            .filter(|field| {
+                let key = field.split('=').next().unwrap_or(field);
+                !options.is_own_key(key) && !fields.iter().any(|(k, _)| k == key)
+            })
+            .collect::<Vec<&str>>()
+            .join(";"),
+    };
+    for (key, value) in fields {
+        if !new_info.is_empty() {
+            new_info.push(';');
+        }
+        new_info.push_str(key);
+        new_info.push('=');
+        new_info.push_str(value);
+    }
+    new_info
+}
+
+fn get_new_id(id: &str, spdi_strings: &[String], id_column: IdColumn) -> String {
+    let spdi_ids: Vec<&str> = spdi_strings
+        .iter()
+        .filter(|v| v.as_str() != ".")
+        .map(|v| v.as_str())
+        .collect();
+    if spdi_ids.is_empty() {
+        return id.to_string();
+    }
+    let spdi_id = spdi_ids.join(";");
+    match id_column {
+        IdColumn::Off => id.to_string(),
+        IdColumn::Replace => spdi_id,
+        IdColumn::Append => match id {
+            "." | "" => spdi_id,
+            _ => {
+                let mut ids: Vec<&str> = id.split(';').collect();
+                for spdi_id in spdi_ids {
+                    if !ids.contains(&spdi_id) {
+                        ids.push(spdi_id);
+                    }
+                }
+                ids.join(";")
+            }
+        },
+    }
+}
+
+fn write_line(out: &mut dyn Write, line: &str) {
+    if let Err(e) = writeln!(out, "{}", line) {
+        eprintln!("Cannot write the output: {}", e);
+        std::process::exit(EXIT_IO);
+    }
+}
+
+fn process_input_file(
+    input_file: &str,
+    spdi: &SPDI,
+    resolver: &ContigResolver,
+    options: &AnnotateOptions,
+    rejects: &mut Option<RejectWriter>,
+    out: &mut dyn Write,
+) -> RunSummary {
+    let f: std::fs::File = match std::fs::File::open(input_file) {
         Err(_) => {
             eprintln!("Cannot open input file: [{}]", input_file);
-            std::process::exit(1);
-        }
-        Ok(v) => {
-            f = v;
+            std::process::exit(EXIT_IO);
         }
-    }
+        Ok(v) => v,
+    };
     let reader = std::io::BufReader::new(f);
     let mut has_sample: bool = false;
+    let mut existing_keys: Vec<String> = Vec::new();
+    let mut resolved_chroms: HashMap<String, Option<String>> = HashMap::default();
+    let mut summary = RunSummary::default();
     let mut line: String;
-    for line_r in reader.lines() {
+    'records: for line_r in reader.lines() {
         match line_r {
             Err(_) => {
                 eprintln!("Error while reading a line from input file");
-                std::process::exit(1);
+                std::process::exit(EXIT_IO);
             }
             Ok(v) => {
                 line = v;
@@ -537,101 +1281,519 @@ fn process_input_file(input_file: &String, spdi: &mut SPDI) {
         }
         if line.starts_with("#CHROM") {
             has_sample = header_has_sample(&line);
-            println!("##INFO=<ID=OV_SPDI_IDS,Number=A,Type=String,Description=\"SPDI notation of each alternate allele\">");
-            println!("{}", line);
+            let mut header_lines = options.header_lines();
+            header_lines.extend(options.provenance_lines(spdi));
+            for (key, header_line) in header_lines {
+                if options.existing == ExistingPolicy::Skip && existing_keys.contains(&key) {
+                    continue;
+                }
+                write_line(out, &header_line);
+            }
+            write_line(out, &line);
+            continue;
+        }
+        if let Some(key) = get_header_info_key(&line) {
+            if options.is_own_key(key) {
+                match options.existing {
+                    ExistingPolicy::Fail => {
+                        eprintln!("Input file already has INFO field [{}].", key);
+                        std::process::exit(EXIT_PARSE);
+                    }
+                    ExistingPolicy::Skip => {
+                        existing_keys.push(key.to_string());
+                        write_line(out, &line);
+                    }
+                    ExistingPolicy::Overwrite => {}
+                }
+                continue;
+            }
+        }
+        if line.starts_with("##contig=") && options.contig_check != ContigCheck::Off {
+            let mismatches = check_contig_line(&line, spdi, resolver, options);
+            for mismatch in mismatches.iter() {
+                match options.contig_check {
+                    ContigCheck::Fail => eprintln!("Error: {}", mismatch),
+                    _ => eprintln!("Warning: {}", mismatch),
+                }
+            }
+            if !mismatches.is_empty() && options.contig_check == ContigCheck::Fail {
+                eprintln!("The VCF file does not seem to match the reference.");
+                std::process::exit(EXIT_REFERENCE);
+            }
+        }
+        if let Some(id) = get_provenance_id(&line) {
+            if options.existing == ExistingPolicy::Skip {
+                existing_keys.push(id.to_string());
+                write_line(out, &line);
+            }
             continue;
         }
         if line.starts_with("#") {
-            println!("{}", line);
+            write_line(out, &line);
             continue;
         }
         let words: Vec<&str> = line.split("\t").collect();
         let words_len = words.len();
         if words_len < 8 {
-            println!("{}", line);
+            write_line(out, &line);
+            continue;
         }
-        let chrom_s = words[0];
-        let chrom_1st_c: char = chrom_s.chars().next().unwrap();
-        let chrom: &[u8];
-        let new_chrom: String;
-        if (chrom_1st_c >= '1' && chrom_1st_c <= '9') || chrom_1st_c == 'X' || chrom_1st_c == 'Y' {
-            new_chrom = format!("chr{}", chrom_s);
-            chrom = new_chrom.as_bytes();
-        } else if chrom_1st_c == 'M' {
-            chrom = "chrM".as_bytes();
-        } else {
-            let chrom_o = CHROMS.get(&chrom_s);
-            match chrom_o {
-                None => {
-                    eprintln!("Chromosome [{}] not supported: {}", chrom_s, line);
-                    continue;
+        summary.records += 1;
+        if get_info_keys(words[7]).any(|key| options.is_own_key(key)) {
+            match options.existing {
+                ExistingPolicy::Fail => {
+                    eprintln!("Input file already has SPDI annotations: {}", line);
+                    std::process::exit(EXIT_PARSE);
                 }
-                Some(v) => {
-                    chrom = v.as_bytes();
+                ExistingPolicy::Skip => {
+                    summary.skipped_records += 1;
+                    write_line(out, &line);
+                    continue;
                 }
+                ExistingPolicy::Overwrite => {}
             }
         }
-        let pos_r = words[1].parse::<usize>();
-        let pos: usize;
-        match pos_r {
+        summary.alleles += words[4].split(',').count();
+        let chrom_s = words[0];
+        if !resolved_chroms.contains_key(chrom_s) {
+            let resolved_chrom = resolver.resolve(chrom_s);
+            warn_mito(resolver, chrom_s, resolved_chrom.as_deref());
+            resolved_chroms.insert(chrom_s.to_string(), resolved_chrom);
+        }
+        let new_chrom: String = match &resolved_chroms[chrom_s] {
+            None => {
+                *summary.unsupported_contigs.entry(chrom_s.to_string()).or_insert(0) += 1;
+                let e = spdi::error::Error::UnknownSequence { name: chrom_s.to_string() };
+                for alt_base in words[4].split(',') {
+                    if is_unsupported_allele(alt_base) {
+                        summary.unsupported_alleles += 1;
+                        continue;
+                    }
+                    summary.add_error(e.kind());
+                    write_reject(rejects, &words, alt_base, e.kind(), &e.to_string());
+                }
+                if summary.has_too_many_errors(options.max_errors) {
+                    summary.aborted = true;
+                    break 'records;
+                }
+                write_line(out, &line);
+                continue;
+            }
+            Some(v) => v.clone(),
+        };
+        let chrom: &[u8] = new_chrom.as_bytes();
+        let pos: usize = match words[1].parse::<usize>() {
             Err(_) => {
                 eprintln!("Invalid POS: {}", line);
-                println!("{}", line);
+                for alt_base in words[4].split(',') {
+                    summary.add_error("InvalidPosition");
+                    write_reject(
+                        rejects,
+                        &words,
+                        alt_base,
+                        "InvalidPosition",
+                        &format!("Invalid POS: {}", words[1]),
+                    );
+                }
+                if summary.has_too_many_errors(options.max_errors) {
+                    summary.aborted = true;
+                    break 'records;
+                }
+                write_line(out, &line);
                 continue;
             }
-            Ok(v) => {
-                pos = v;
+            Ok(v) => v,
+        };
+        let ref_base: &[u8] = words[3].as_bytes();
+        if options.check_reference {
+            if let Err(e) = spdi.check_reference(chrom, pos, ref_base) {
+                eprintln!("{}: {}", e, line);
+                for alt_base in words[4].split(',') {
+                    summary.add_error(e.kind());
+                    write_reject(rejects, &words, alt_base, e.kind(), &e.to_string());
+                }
+                if summary.has_too_many_errors(options.max_errors) {
+                    summary.aborted = true;
+                    break 'records;
+                }
+                write_line(out, &line);
+                continue;
             }
         }
-        let ref_base: &[u8] = words[3].as_bytes();
         let alt_bases = words[4].split(",");
         let mut spdi_strings: Vec<String> = Vec::with_capacity(4);
+        let mut spdi_poss: Vec<String> = Vec::with_capacity(4);
+        let mut spdi_refs: Vec<String> = Vec::with_capacity(4);
+        let mut spdi_alts: Vec<String> = Vec::with_capacity(4);
+        let mut spdi_classes: Vec<&str> = Vec::with_capacity(4);
+        let mut spdi_shiftables: Vec<&str> = Vec::with_capacity(4);
+        let mut spdi_repeats: Vec<[String; 4]> = Vec::with_capacity(4);
+        let mut spdi_left_shifts: Vec<String> = Vec::with_capacity(4);
+        let mut spdi_right_shifts: Vec<String> = Vec::with_capacity(4);
+        let spdi_chrom = options.get_spdi_chrom(chrom_s, &new_chrom, resolver);
         for alt_base in alt_bases {
-            match spdi.get_spdi_string(&chrom, pos, ref_base, alt_base.as_bytes()) {
-                Err(e) => {
-                    eprintln!("{}: {}", e, line);
+            let converted = match is_unsupported_allele(alt_base) {
+                true => {
+                    summary.unsupported_alleles += 1;
+                    None
+                }
+                false => match spdi.get_classified_spdi_str(chrom, pos, ref_base, alt_base.as_bytes()) {
+                    Err(e) => {
+                        eprintln!("{}: {}", e, line);
+                        write_reject(rejects, &words, alt_base, e.kind(), &e.to_string());
+                        summary.add_error(e.kind());
+                        if summary.has_too_many_errors(options.max_errors) {
+                            summary.aborted = true;
+                            break 'records;
+                        }
+                        None
+                    }
+                    Ok(v) => Some(v),
+                },
+            };
+            match converted {
+                None => {
                     spdi_strings.push(".".to_string());
+                    spdi_poss.push(".".to_string());
+                    spdi_refs.push(".".to_string());
+                    spdi_alts.push(".".to_string());
+                    spdi_classes.push(".");
+                    spdi_shiftables.push(".");
+                    spdi_repeats.push(get_repeat_fields(None));
+                    spdi_left_shifts.push(".".to_string());
+                    spdi_right_shifts.push(".".to_string());
                 }
-                Ok(v) => {
-                    spdi_strings.push(v);
+                Some(converted) => {
+                    let spdi_string = format!(
+                        "{}:{}:{}:{}",
+                        spdi_chrom, converted.pos, converted.ref_bases, converted.alt_bases
+                    );
+                    let change = AlleleChange::new(&converted);
+                    summary.add_allele(&words, pos, alt_base, &spdi_string, &change);
+                    spdi_strings.push(spdi_string);
+                    spdi_poss.push(converted.pos.to_string());
+                    spdi_refs.push(converted.ref_bases);
+                    spdi_alts.push(converted.alt_bases);
+                    spdi_classes.push(converted.class.as_str());
+                    spdi_shiftables.push(match converted.shiftable {
+                        true => "1",
+                        false => "0",
+                    });
+                    spdi_repeats.push(get_repeat_fields(converted.repeat.as_ref()));
+                    spdi_left_shifts.push(converted.shifts.grown_left.to_string());
+                    spdi_right_shifts.push(converted.shifts.grown_right.to_string());
                 }
             }
         }
-        let spdi_string = spdi_strings.join(",");
-        let first = words[0..8].join("\t");
+        let mut info_fields: Vec<(String, String)> =
+            vec![(options.info_key.clone(), spdi_strings.join(","))];
+        if options.split_fields {
+            info_fields.push((format!("{}_POS", options.info_key), spdi_poss.join(",")));
+            info_fields.push((format!("{}_REF", options.info_key), spdi_refs.join(",")));
+            info_fields.push((format!("{}_ALT", options.info_key), spdi_alts.join(",")));
+        }
+        if options.class_fields {
+            info_fields.push((format!("{}_CLASS", options.info_key), spdi_classes.join(",")));
+            info_fields.push((format!("{}_SHIFTABLE", options.info_key), spdi_shiftables.join(",")));
+        }
+        if options.repeat_fields {
+            for (i, suffix) in ["MOTIF", "PERIOD", "REF_COPIES", "ALT_COPIES"].iter().enumerate() {
+                let values: Vec<&str> = spdi_repeats.iter().map(|v| v[i].as_str()).collect();
+                info_fields.push((format!("{}_{}", options.info_key, suffix), values.join(",")));
+            }
+        }
+        if options.shift_fields {
+            info_fields.push((format!("{}_LEFT_SHIFT", options.info_key), spdi_left_shifts.join(",")));
+            info_fields.push((format!("{}_RIGHT_SHIFT", options.info_key), spdi_right_shifts.join(",")));
+        }
+        let new_id = get_new_id(words[2], &spdi_strings, options.id_column);
+        let new_info = get_new_info(words[7], &info_fields, options);
+        let mut new_words: Vec<&str> = words.clone();
+        new_words[2] = &new_id;
+        new_words[7] = &new_info;
         if !has_sample {
-            println!("{};OV_SPDI_IDS={}", first, spdi_string);
-        } else {
-            let last = words[8..].join("\t");
-            println!("{};OV_SPDI_IDS={}\t{}", first, spdi_string, last);
+            new_words.truncate(8);
         }
+        write_line(out, &new_words.join("\t"));
+    }
+    for (chrom_s, count) in summary.unsupported_contigs.iter() {
+        eprintln!(
+            "Chromosome [{}] not found in the reference. {} records were not annotated.",
+            chrom_s, count
+        );
+    }
+    if summary.aborted {
+        eprintln!(
+            "Stopped after {} alleles could not be converted.",
+            summary.errors.values().sum::<usize>()
+        );
     }
+    if let Some(rejects) = rejects.as_mut() {
+        if let Err(e) = rejects.writer.flush() {
+            eprintln!("Cannot write to the reject file: {}", e);
+            std::process::exit(EXIT_IO);
+        }
+    }
+    if let Err(e) = out.flush() {
+        eprintln!("Cannot write the output: {}", e);
+        std::process::exit(EXIT_IO);
+    }
+    summary
 }
 
-fn main() {
-    let cli = Cli::parse();
-    let twobit_path = PathBuf::from(&cli.twobit_path);
-    let spdi_r = SPDI::new(&twobit_path);
-    let mut spdi: SPDI;
-    match spdi_r {
+fn write_summary(summary: &RunSummary, path: &str) {
+    let text = match serde_json::to_string_pretty(&summary.to_json()) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Cannot write summary statistics: {}", e);
+            std::process::exit(EXIT_IO);
+        }
+    };
+    if path == "-" {
+        eprintln!("{}", text);
+    } else if let Err(e) = std::fs::write(path, text + "\n") {
+        eprintln!("Cannot write summary statistics to [{}]: {}", path, e);
+        std::process::exit(EXIT_IO);
+    }
+}
+
+/// Opens the reference and reads contig aliases and circular sequences.
+fn open_reference(args: &ReferenceArgs) -> (SPDI, ContigResolver) {
+    let twobit_path = PathBuf::from(&args.twobit_path);
+    let mut spdi: SPDI = match SPDI::new(&twobit_path) {
         Err(_) => {
-            eprintln!("Cannot open a 2bit file at [{}].", cli.twobit_path);
-            std::process::exit(1);
-        }
-        Ok(v) => spdi = v,
-    }
-    let variant_len = cli.variant.len();
-    let input_file_len = cli.input_file.len();
-    match variant_len {
-        0 => match input_file_len {
-            0 => {
-                eprintln!("-v <variant> or -f <input_file> should be given.");
-                std::process::exit(1);
+            eprintln!("Cannot open a 2bit file at [{}].", args.twobit_path);
+            std::process::exit(EXIT_REFERENCE);
+        }
+        Ok(v) => v,
+    };
+    let mut resolver = spdi.contig_resolver();
+    if let Some(path) = args.contig_aliases.as_ref() {
+        if let Err(e) = resolver.read_aliases(&PathBuf::from(path)) {
+            eprintln!("Cannot read a contig alias file at [{}]: {}", path, e);
+            std::process::exit(EXIT_IO);
+        }
+    }
+    for chrom_s in args.circular.iter() {
+        match resolver.resolve(chrom_s) {
+            Some(chrom) => spdi.set_circular(&chrom, true),
+            None => {
+                eprintln!("Circular sequence [{}] is not in the reference.", chrom_s);
+                std::process::exit(EXIT_REFERENCE);
             }
-            _ => {
-                process_input_file(&cli.input_file, &mut spdi);
+        }
+    }
+    (spdi, resolver)
+}
+
+fn convert(args: &ConvertArgs) {
+    let (spdi, resolver) = open_reference(&args.reference);
+    let mut writer = ConversionWriter::new(args.format);
+    match args.input.as_ref() {
+        Some(input) => convert_batch(input, args, &spdi, &resolver, &mut writer),
+        None => {
+            for variant in args.variants.iter() {
+                let input = parse_variant(variant);
+                let result = match &input {
+                    Ok(v) => convert_alleles(*v, &spdi, &resolver),
+                    Err(e) => Err(e.clone()),
+                };
+                writer.write(variant, None, input.as_ref().ok(), &result);
+            }
+        }
+    }
+    std::process::exit(writer.finish());
+}
+
+/// Converts each line of the input. Lines starting with # are not variants.
+fn convert_batch(
+    input: &str,
+    args: &ConvertArgs,
+    spdi: &SPDI,
+    resolver: &ContigResolver,
+    writer: &mut ConversionWriter,
+) {
+    if !(args.columns.is_empty() || args.columns.len() == 4) || args.columns.contains(&0) {
+        eprintln!("--columns should be four 1-based column numbers of chrom, pos, ref and alt.");
+        std::process::exit(EXIT_USAGE);
+    }
+    let reader: Box<dyn BufRead> = match input {
+        "-" => Box::new(std::io::stdin().lock()),
+        _ => match std::fs::File::open(input) {
+            Ok(f) => Box::new(std::io::BufReader::new(f)),
+            Err(_) => {
+                eprintln!("Cannot open input file: [{}]", input);
+                std::process::exit(EXIT_IO);
             }
         },
-        _ => process_variant(&cli.variant, &mut spdi),
+    };
+    for (i, line_r) in reader.lines().enumerate() {
+        let line = match line_r {
+            Ok(v) => v,
+            Err(_) => {
+                eprintln!("Error while reading a line from input file");
+                std::process::exit(EXIT_IO);
+            }
+        };
+        if i == 0 && args.header {
+            writer.write_other(&format!("{}\tSPDI", line));
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') {
+            writer.write_other(&line);
+            continue;
+        }
+        let input = parse_line(&line, &args.columns);
+        let result = match &input {
+            Ok(v) => convert_alleles(*v, spdi, resolver),
+            Err(e) => Err(e.clone()),
+        };
+        writer.write(&line, Some(i + 1), input.as_ref().ok(), &result);
+    }
+}
+
+fn annotate_vcf(args: &AnnotateVcfArgs) {
+    let (spdi, resolver) = open_reference(&args.reference);
+    let options = AnnotateOptions::from_args(args);
+    let is_style = !matches!(options.contig_naming, ContigNamingArg::Reference | ContigNamingArg::Input);
+    if is_style && !resolver.is_grch38() {
+        eprintln!(
+            "--contig-naming {} needs a GRCh38 reference, and [{}] is not one.",
+            options.contig_naming.to_possible_value().unwrap().get_name(),
+            args.reference.twobit_path
+        );
+        std::process::exit(EXIT_REFERENCE);
+    }
+    let mut rejects: Option<RejectWriter> = match args.reject_file.as_ref() {
+        None => None,
+        Some(path) => match RejectWriter::create(path, args.reject_format) {
+            Ok(v) => Some(v),
+            Err(e) => {
+                eprintln!("Cannot create a reject file at [{}]: {}", path, e);
+                std::process::exit(EXIT_IO);
+            }
+        },
+    };
+    let mut out = BufWriter::new(std::io::stdout().lock());
+    let summary =
+        process_input_file(&args.input_file, &spdi, &resolver, &options, &mut rejects, &mut out);
+    if let Some(path) = args.summary_file.as_ref() {
+        write_summary(&summary, path);
+    }
+    if summary.aborted {
+        std::process::exit(EXIT_CONVERSION);
+    }
+}
+
+/// Writes a row for each allele which cannot be converted, or whose record
+/// has REF bases different from the reference or a contig which is not in it.
+fn validate(args: &ValidateArgs) {
+    let (spdi, resolver) = open_reference(&args.reference);
+    let options = AnnotateOptions {
+        check_reference: true,
+        ..AnnotateOptions::new(&args.reference, &args.contig_check)
+    };
+    let mut rejects = match RejectWriter::new(Box::new(std::io::stdout().lock()), args.format) {
+        Ok(v) => Some(v),
+        Err(e) => {
+            eprintln!("Cannot write the output: {}", e);
+            std::process::exit(EXIT_IO);
+        }
+    };
+    let summary = process_input_file(
+        &args.input_file,
+        &spdi,
+        &resolver,
+        &options,
+        &mut rejects,
+        &mut std::io::sink(),
+    );
+    if summary.errors.contains_key("ReferenceMismatch") || !summary.unsupported_contigs.is_empty() {
+        std::process::exit(EXIT_REFERENCE);
+    }
+    if !summary.errors.is_empty() {
+        std::process::exit(EXIT_CONVERSION);
+    }
+}
+
+fn to_vcf(args: &ToVcfArgs) {
+    let reference = args.twobit_path.as_ref().map(|twobit_path| {
+        open_reference(&ReferenceArgs {
+            twobit_path: twobit_path.clone(),
+            contig_aliases: args.contig_aliases.clone(),
+            circular: Vec::new(),
+        })
+    });
+    let spdi = reference.as_ref().map(|(spdi, _)| spdi);
+    let resolver = reference.as_ref().map(|(_, resolver)| resolver);
+    println!("##fileformat=VCFv4.2");
+    println!("#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO");
+    let mut exit_code = 0;
+    for spdi_s in args.spdis.iter() {
+        match get_vcf_of_spdi(spdi_s, spdi, resolver) {
+            Ok((chrom, pos, ref_base, alt_base)) => println!(
+                "{}\t{}\t{}\t{}\t{}\t.\t.\t.",
+                chrom, pos, spdi_s, ref_base, alt_base
+            ),
+            Err(e) => {
+                eprintln!("Error: {}", e.message);
+                exit_code = e.code;
+            }
+        }
+    }
+    std::process::exit(exit_code);
+}
+
+/// Prints the SPDIs of the two variants and whether they are the same. Exits
+/// with 1 if they are different.
+fn compare(args: &CompareArgs) {
+    let (spdi, resolver) = open_reference(&args.reference);
+    let spdi1 = convert_variant(&args.variant1, &spdi, &resolver);
+    let spdi2 = convert_variant(&args.variant2, &spdi, &resolver);
+    let (spdi1, spdi2) = match (spdi1, spdi2) {
+        (Ok(v1), Ok(v2)) => (v1, v2),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("Error: {}", e.message);
+            std::process::exit(e.code);
+        }
+    };
+    let same = spdi1 == spdi2;
+    println!("{}\t{}\t{}", spdi1, spdi2, if same { "same" } else { "different" });
+    if !same {
+        std::process::exit(1);
+    }
+}
+
+fn stats(args: &StatsArgs) {
+    let (spdi, resolver) = open_reference(&args.reference);
+    let options = AnnotateOptions::new(&args.reference, &args.contig_check);
+    let summary = process_input_file(
+        &args.input_file,
+        &spdi,
+        &resolver,
+        &options,
+        &mut None,
+        &mut std::io::sink(),
+    );
+    match serde_json::to_string_pretty(&summary.to_json()) {
+        Ok(v) => println!("{}", v),
+        Err(e) => {
+            eprintln!("Cannot write summary statistics: {}", e);
+            std::process::exit(EXIT_IO);
+        }
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    match &cli.command {
+        Command::Convert(args) => convert(args),
+        Command::AnnotateVcf(args) => annotate_vcf(args),
+        Command::Validate(args) => validate(args),
+        Command::ToVcf(args) => to_vcf(args),
+        Command::Compare(args) => compare(args),
+        Command::Stats(args) => stats(args),
+        #[cfg(feature = "serve")]
+        Command::Serve(args) => serve::serve(args),
     }
 }
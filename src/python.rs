@@ -0,0 +1,238 @@
+// Copyright Ryangguk Kim @ Oak Bioinformatics, LLC
+//
+// This software is available under a dual licensing model, offering users the choice between the Affero General Public License version 3 (AGPL-3) for open-source use and a commercial license for proprietary or commercial use.
+//
+// To obtain a commercial license, please contact info@oakbioinformatics.com.
+
+//! Python bindings, built with the `python` feature.
+//!
+//! ```python
+//! import spdi
+//! s = spdi.SPDI("hg38.2bit")
+//! s.to_spdi("chr1", 99092, "C", "CT")
+//! df["spdi"] = s.to_spdis(df["chrom"], df["pos"], df["ref"], df["alt"], errors="coerce")
+//! ```
+
+use crate::contig::ContigResolver;
+use crate::error::Error;
+use crate::SPDI;
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+use std::path::PathBuf;
+
+/// Position, reference bases and alternate bases of a variant with its sequence name.
+type Variant = (String, usize, String, String);
+
+/// SPDI converter on a 2bit or FASTA reference. Sequence names are resolved
+/// like in the `spdi` binary, so chr1, 1 and NC_000001.11 all work on hg38.
+#[pyclass(name = "SPDI", module = "spdi", frozen)]
+struct PySpdi {
+    spdi: SPDI,
+    resolver: ContigResolver,
+}
+
+impl PySpdi {
+    fn convert(&self, chrom: &str, pos: usize, ref_base: &str, alt_base: &str) -> Result<Variant, Error> {
+        let new_chrom = self.resolver.resolve(chrom).unwrap_or(chrom.to_string());
+        // - is an empty allele, as in SPDIs.
+        let ref_base = match ref_base {
+            "-" => "",
+            v => v,
+        };
+        let alt_base = match alt_base {
+            "-" => "",
+            v => v,
+        };
+        let (new_pos, new_ref, new_alt) = self.spdi.get_spdi_string_components_str(
+            new_chrom.as_bytes(),
+            pos,
+            ref_base.as_bytes(),
+            alt_base.as_bytes(),
+        )?;
+        Ok((new_chrom, new_pos, new_ref, new_alt))
+    }
+
+    /// Converts variants given as columns. With `errors="coerce"`, variants
+    /// which cannot be converted give `None`, and with `errors="raise"` the
+    /// first of them raises `ValueError`.
+    fn convert_all(
+        &self,
+        py: Python<'_>,
+        columns: (Vec<String>, Vec<usize>, Vec<String>, Vec<String>),
+        errors: &str,
+    ) -> PyResult<Vec<Option<Variant>>> {
+        let (chroms, poss, ref_bases, alt_bases) = columns;
+        if poss.len() != chroms.len() || ref_bases.len() != chroms.len() || alt_bases.len() != chroms.len() {
+            return Err(PyValueError::new_err("chrom, pos, ref and alt should have the same length"));
+        }
+        let coerce = is_coerce(errors)?;
+        py.allow_threads(|| {
+            (0..chroms.len())
+                .map(|i| match self.convert(&chroms[i], poss[i], &ref_bases[i], &alt_bases[i]) {
+                    Ok(v) => Ok(Some(v)),
+                    Err(_) if coerce => Ok(None),
+                    Err(e) => Err(PyValueError::new_err(e.to_string())),
+                })
+                .collect()
+        })
+    }
+}
+
+/// Whether variants which cannot be converted give `None` rather than raise.
+fn is_coerce(errors: &str) -> PyResult<bool> {
+    match errors {
+        "raise" => Ok(false),
+        "coerce" => Ok(true),
+        _ => Err(PyValueError::new_err("errors should be \"raise\" or \"coerce\"")),
+    }
+}
+
+/// Extracts a list from any iterable, such as a list or a pandas Series.
+fn extract_list<'py, T: FromPyObject<'py>>(obj: &Bound<'py, PyAny>) -> PyResult<Vec<T>> {
+    obj.try_iter()?.map(|v| v?.extract::<T>()).collect()
+}
+
+#[pymethods]
+impl PySpdi {
+    /// Loads a 2bit or an uncompressed FASTA file. `circular` names sequences
+    /// whose indels are normalized across the origin, such as chrM.
+    #[new]
+    #[pyo3(signature = (path, circular=None))]
+    fn new(path: PathBuf, circular: Option<Vec<String>>) -> PyResult<Self> {
+        if !path.exists() {
+            return Err(PyIOError::new_err(format!("No such file: {}", path.display())));
+        }
+        let mut spdi = SPDI::new(&path).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let resolver = spdi.contig_resolver();
+        for chrom in circular.unwrap_or_default() {
+            match resolver.resolve(&chrom) {
+                Some(v) => spdi.set_circular(&v, true),
+                None => {
+                    return Err(PyValueError::new_err(format!(
+                        "Circular sequence [{}] is not in the reference.",
+                        chrom
+                    )))
+                }
+            }
+        }
+        Ok(PySpdi { spdi, resolver })
+    }
+
+    /// Names of the sequences in the reference.
+    fn sequence_names(&self) -> Vec<String> {
+        self.spdi.sequence_names().to_vec()
+    }
+
+    /// Returns the SPDI of a variant as a string such as `chr1:99092:C:CT`.
+    #[pyo3(signature = (chrom, pos, ref_base, alt_base))]
+    fn to_spdi(&self, chrom: &str, pos: usize, ref_base: &str, alt_base: &str) -> PyResult<String> {
+        self.to_spdi_tuple(chrom, pos, ref_base, alt_base)
+            .map(|(chrom, pos, ref_base, alt_base)| format!("{}:{}:{}:{}", chrom, pos, ref_base, alt_base))
+    }
+
+    /// Returns the SPDI of a variant as a `(chrom, pos, ref, alt)` tuple.
+    #[pyo3(signature = (chrom, pos, ref_base, alt_base))]
+    fn to_spdi_tuple(&self, chrom: &str, pos: usize, ref_base: &str, alt_base: &str) -> PyResult<Variant> {
+        self.convert(chrom, pos, ref_base, alt_base)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Returns the SPDI strings of variants given as iterables of the same length.
+    #[pyo3(signature = (chroms, poss, ref_bases, alt_bases, errors="raise"))]
+    fn to_spdis(
+        &self,
+        py: Python<'_>,
+        chroms: &Bound<'_, PyAny>,
+        poss: &Bound<'_, PyAny>,
+        ref_bases: &Bound<'_, PyAny>,
+        alt_bases: &Bound<'_, PyAny>,
+        errors: &str,
+    ) -> PyResult<Vec<Option<String>>> {
+        let columns = (
+            extract_list(chroms)?,
+            extract_list(poss)?,
+            extract_list(ref_bases)?,
+            extract_list(alt_bases)?,
+        );
+        Ok(self
+            .convert_all(py, columns, errors)?
+            .into_iter()
+            .map(|v| v.map(|(chrom, pos, ref_base, alt_base)| format!("{}:{}:{}:{}", chrom, pos, ref_base, alt_base)))
+            .collect())
+    }
+
+    /// Same as `to_spdis` but returns `(chrom, pos, ref, alt)` tuples.
+    #[pyo3(signature = (chroms, poss, ref_bases, alt_bases, errors="raise"))]
+    fn to_spdi_tuples(
+        &self,
+        py: Python<'_>,
+        chroms: &Bound<'_, PyAny>,
+        poss: &Bound<'_, PyAny>,
+        ref_bases: &Bound<'_, PyAny>,
+        alt_bases: &Bound<'_, PyAny>,
+        errors: &str,
+    ) -> PyResult<Vec<Option<Variant>>> {
+        let columns = (
+            extract_list(chroms)?,
+            extract_list(poss)?,
+            extract_list(ref_bases)?,
+            extract_list(alt_bases)?,
+        );
+        self.convert_all(py, columns, errors)
+    }
+}
+
+#[pymodule]
+fn spdi(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PySpdi>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests_python {
+    use super::{is_coerce, PySpdi};
+    use crate::error::Error;
+    use crate::tests::write_twobit;
+    use crate::SPDI;
+
+    fn get_py_spdi(name: &str) -> PySpdi {
+        let path = write_twobit(name, &[("chr1", "GCGTTATTATTAGCG")]);
+        let spdi = SPDI::new(&path).unwrap();
+        let resolver = spdi.contig_resolver();
+        PySpdi { spdi, resolver }
+    }
+
+    #[test]
+    fn test_convert() {
+        let py_spdi = get_py_spdi("python-convert");
+        assert_eq!(
+            ("chr1".to_string(), 2, "C".to_string(), "G".to_string()),
+            py_spdi.convert("chr1", 2, "C", "G").unwrap()
+        );
+        // - is an empty allele.
+        assert_eq!(
+            ("chr1".to_string(), 4, "TTATTATTA".to_string(), "TTATTA".to_string()),
+            py_spdi.convert("chr1", 7, "TTA", "-").unwrap()
+        );
+        assert!(matches!(py_spdi.convert("chrZ", 2, "C", "G"), Err(Error::UnknownSequence { .. })));
+    }
+
+    #[test]
+    fn test_convert_dash() {
+        let py_spdi = get_py_spdi("python-dash");
+        // Only an exact - is an empty allele.
+        for alt_base in ["-A", "--"] {
+            let e = py_spdi.convert("chr1", 2, "C", alt_base).unwrap_err();
+            assert!(matches!(e, Error::InvalidBase { .. }));
+            assert_eq!("Wrong base: -", e.to_string());
+        }
+        assert!(py_spdi.convert("chr1", 7, "-TTA", "").is_err());
+    }
+
+    #[test]
+    fn test_is_coerce() {
+        assert!(!is_coerce("raise").unwrap());
+        assert!(is_coerce("coerce").unwrap());
+        assert!(is_coerce("ignore").is_err());
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright Ryangguk Kim @ Oak Bioinformatics, LLC
+//
+// This software is available under a dual licensing model, offering users the choice between the Affero General Public License version 3 (AGPL-3) for open-source use and a commercial license for proprietary or commercial use.
+//
+// To obtain a commercial license, please contact info@oakbioinformatics.com.
+
+//! Tandem repeats around shiftable indels.
+//!
+//! The SPDI of a shiftable indel covers the whole repeat which the indel can
+//! move in, and both of its alleles repeat the inserted or deleted bases. The
+//! motif is the shortest unit of those bases, rotated to start where the
+//! repeat starts.
+
+use crate::util::get_string_of_bases;
+use crate::Base;
+
+/// Tandem repeat which a shiftable indel changes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TandemRepeat {
+    /// Shortest repeated unit, such as `ATTCT`.
+    pub motif: String,
+    /// Whole copies of the motif in the reference allele of the SPDI.
+    pub ref_copies: usize,
+    /// Whole copies of the motif in the alternate allele of the SPDI.
+    pub alt_copies: usize,
+}
+
+impl TandemRepeat {
+    /// Length of the motif.
+    pub fn period(&self) -> usize {
+        self.motif.len()
+    }
+}
+
+/// Returns the length of the shortest unit which `bases` are copies of.
+pub(crate) fn get_period(bases: &[Base]) -> usize {
+    let len = bases.len();
+    (1..len)
+        .filter(|p| len % p == 0)
+        .find(|p| bases.iter().zip(&bases[*p..]).all(|(a, b)| a == b))
+        .unwrap_or(len)
+}
+
+/// Returns the repeat of an indel from the bases of its SPDI, where
+/// `indel_len` bases were inserted or deleted.
+pub(crate) fn get_tandem_repeat(
+    ref_bases: &[Base],
+    alt_bases: &[Base],
+    indel_len: usize,
+) -> Option<TandemRepeat> {
+    let longer = match ref_bases.len() > alt_bases.len() {
+        true => ref_bases,
+        false => alt_bases,
+    };
+    if indel_len == 0 || longer.len() < indel_len {
+        return None;
+    }
+    let unit = &longer[..indel_len];
+    let period = get_period(unit);
+    Some(TandemRepeat {
+        motif: get_string_of_bases(&unit[..period]),
+        ref_copies: ref_bases.len() / period,
+        alt_copies: alt_bases.len() / period,
+    })
+}
+
+#[cfg(test)]
+mod tests_repeat {
+    use super::*;
+    use crate::util::get_bases_of_vu8;
+
+    #[test]
+    fn test_get_tandem_repeat() {
+        let bases = |s: &str| get_bases_of_vu8(s.as_bytes()).unwrap();
+        assert_eq!(5, get_period(&bases("ATTCTATTCT")));
+        assert_eq!(4, get_period(&bases("ATTA")));
+        let repeat = get_tandem_repeat(&bases("ATTCTATTCT"), &bases("ATTCTATTCTATTCTATTCT"), 10).unwrap();
+        assert_eq!(("ATTCT", 5, 2, 4), (repeat.motif.as_str(), repeat.period(), repeat.ref_copies, repeat.alt_copies));
+        let repeat = get_tandem_repeat(&bases("TTATTATTATTATT"), &bases("TTATTATTATT"), 3).unwrap();
+        assert_eq!(("TTA", 4, 3), (repeat.motif.as_str(), repeat.ref_copies, repeat.alt_copies));
+        assert_eq!(None, get_tandem_repeat(&bases("A"), &bases("G"), 0));
+    }
+}
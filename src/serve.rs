@@ -0,0 +1,263 @@
+// Copyright Ryangguk Kim @ Oak Bioinformatics, LLC
+//
+// This software is available under a dual licensing model, offering users the choice between the Affero General Public License version 3 (AGPL-3) for open-source use and a commercial license for proprietary or commercial use.
+//
+// To obtain a commercial license, please contact info@oakbioinformatics.com.
+
+//! `spdi serve`: a local HTTP service which loads the reference once.
+//!
+//! Endpoints are modelled on NCBI Variation Services, but positions are
+//! 1-based like everywhere else in `spdi`.
+//!
+//! - `GET /vcf/{chrom}/{pos}/{ref}/{alt}/contextuals`
+//! - `GET /spdi/{spdi}/canonical_representative`
+//! - `POST /vcf/contextuals` and `POST /spdi/canonical_representative` with one
+//!   variant or SPDI per line, answered with the objects of `convert --format json`.
+
+use crate::{
+    convert_alleles, get_conversion_json, open_reference, parse_variant, ConvertError,
+    ConvertedAllele, ReferenceArgs, EXIT_IO,
+};
+use clap::Args;
+use spdi::contig::ContigResolver;
+use spdi::SPDI;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+#[derive(Args)]
+pub struct ServeArgs {
+    #[command(flatten)]
+    reference: ReferenceArgs,
+    #[arg(help = "Address to listen on")]
+    #[arg(id = "host")]
+    #[arg(long = "host")]
+    #[arg(default_value = "127.0.0.1")]
+    host: String,
+    #[arg(help = "Port to listen on")]
+    #[arg(id = "port")]
+    #[arg(long = "port")]
+    #[arg(default_value_t = 8080)]
+    port: u16,
+    #[arg(help = "Number of threads handling requests")]
+    #[arg(id = "threads")]
+    #[arg(long = "threads")]
+    #[arg(default_value_t = 4)]
+    threads: usize,
+}
+
+pub fn serve(args: &ServeArgs) {
+    let (spdi, resolver) = open_reference(&args.reference);
+    let server = match Server::http((args.host.as_str(), args.port)) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Cannot listen on {}:{}: {}", args.host, args.port, e);
+            std::process::exit(EXIT_IO);
+        }
+    };
+    eprintln!("Listening on http://{}:{}", args.host, args.port);
+    std::thread::scope(|scope| {
+        for _ in 0..args.threads.max(1) {
+            scope.spawn(|| {
+                for request in server.incoming_requests() {
+                    handle_request(request, &spdi, &resolver);
+                }
+            });
+        }
+    });
+}
+
+/// Status and JSON body of a response.
+type Reply = (u16, serde_json::Value);
+
+/// What a request asks for, decided from its method and path alone.
+#[derive(Debug, PartialEq)]
+enum Endpoint {
+    /// A variant given as `chrom:pos:ref:alt`.
+    Contextuals(String),
+    /// An SPDI.
+    CanonicalRepresentative(String),
+    /// Variants or SPDIs in the request body.
+    Batch,
+    NotAllowed,
+    NotFound,
+}
+
+fn route(method: &Method, path: &str) -> Endpoint {
+    let segments: Vec<String> = path
+        .split('/')
+        .filter(|v| !v.is_empty())
+        .map(percent_decode)
+        .collect();
+    let segments: Vec<&str> = segments.iter().map(|v| v.as_str()).collect();
+    match (method, segments.as_slice()) {
+        (Method::Get, ["vcf", chrom, pos, ref_base, alt_base, "contextuals"]) => {
+            Endpoint::Contextuals(format!("{}:{}:{}:{}", chrom, pos, ref_base, alt_base))
+        }
+        (Method::Get, ["spdi", spdi_s, "canonical_representative"]) => {
+            Endpoint::CanonicalRepresentative(spdi_s.to_string())
+        }
+        (Method::Post, ["vcf", "contextuals"]) | (Method::Post, ["spdi", "canonical_representative"]) => {
+            Endpoint::Batch
+        }
+        (_, ["vcf", _, _, _, _, "contextuals"])
+        | (_, ["spdi", _, "canonical_representative"])
+        | (_, ["vcf", "contextuals"])
+        | (_, ["spdi", "canonical_representative"]) => Endpoint::NotAllowed,
+        _ => Endpoint::NotFound,
+    }
+}
+
+fn handle_request(mut request: Request, spdi: &SPDI, resolver: &ContigResolver) {
+    let path = request.url().split('?').next().unwrap_or("").to_string();
+    let reply: Reply = match route(request.method(), &path) {
+        Endpoint::Contextuals(variant) => get_contextuals(&variant, spdi, resolver),
+        Endpoint::CanonicalRepresentative(spdi_s) => get_canonical_representative(&spdi_s, spdi, resolver),
+        Endpoint::Batch => {
+            let mut body = String::new();
+            match request.as_reader().read_to_string(&mut body) {
+                Ok(_) => get_batch(&body, spdi, resolver),
+                Err(e) => get_error_reply(400, &format!("Cannot read the request body: {}", e)),
+            }
+        }
+        Endpoint::NotAllowed => get_error_reply(405, &format!("Method {} is not allowed.", request.method())),
+        Endpoint::NotFound => get_error_reply(404, &format!("Not found: {}", path)),
+    };
+    let (status, json) = reply;
+    let response = Response::from_string(json.to_string())
+        .with_status_code(status)
+        .with_header(
+            Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+                .expect("static header is valid"),
+        );
+    if let Err(e) = request.respond(response) {
+        log::debug!("Cannot send a response: {}", e);
+    }
+}
+
+fn convert(variant: &str, spdi: &SPDI, resolver: &ContigResolver) -> Result<ConvertedAllele, ConvertError> {
+    convert_alleles(parse_variant(variant)?, spdi, resolver)
+}
+
+fn get_spdi_json(converted: &ConvertedAllele) -> serde_json::Value {
+    serde_json::json!({
+        "seq_id": converted.chrom,
+        "position": converted.pos,
+        "deleted_sequence": converted.ref_base.trim_start_matches('-'),
+        "inserted_sequence": converted.alt_base.trim_start_matches('-'),
+    })
+}
+
+fn get_contextuals(variant: &str, spdi: &SPDI, resolver: &ContigResolver) -> Reply {
+    match convert(variant, spdi, resolver) {
+        Ok(v) => (200, serde_json::json!({"data": {"spdis": [get_spdi_json(&v)]}})),
+        Err(e) => get_error_reply(400, &e.message),
+    }
+}
+
+fn get_canonical_representative(spdi_s: &str, spdi: &SPDI, resolver: &ContigResolver) -> Reply {
+    match convert(spdi_s, spdi, resolver) {
+        Ok(v) => (200, serde_json::json!({"data": get_spdi_json(&v)})),
+        Err(e) => get_error_reply(400, &e.message),
+    }
+}
+
+/// Converts each non-empty line of a request body. Errors are reported per
+/// variant, so the reply is 200 as long as the body could be read.
+fn get_batch(body: &str, spdi: &SPDI, resolver: &ContigResolver) -> Reply {
+    let data: Vec<serde_json::Value> = body
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let input = parse_variant(line.trim());
+            let result = match &input {
+                Ok(v) => convert_alleles(*v, spdi, resolver),
+                Err(e) => Err(e.clone()),
+            };
+            get_conversion_json(line, Some(i + 1), input.as_ref().ok(), &result)
+        })
+        .collect();
+    (200, serde_json::json!({ "data": data }))
+}
+
+fn get_error_reply(status: u16, message: &str) -> Reply {
+    (
+        status,
+        serde_json::json!({"error": {"code": status, "message": message}}),
+    )
+}
+
+/// Decodes %XX escapes of a URL path segment.
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = match bytes[i] {
+            b'%' => bytes
+                .get(i + 1..i + 3)
+                .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+        match byte {
+            Some(v) => {
+                decoded.push(v);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+#[cfg(all(test, feature = "serve"))]
+mod tests_serve {
+    use super::{get_error_reply, percent_decode, route, Endpoint};
+    use tiny_http::Method;
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!("chr1", percent_decode("chr1"));
+        assert_eq!("NC_000001.11:1:C:G", percent_decode("NC_000001.11%3A1%3aC%3AG"));
+        assert_eq!("a b", percent_decode("a%20b"));
+        // Truncated and non-hex escapes are kept as they are.
+        assert_eq!("%", percent_decode("%"));
+        assert_eq!("chr1%3", percent_decode("chr1%3"));
+        assert_eq!("%ZZ1", percent_decode("%ZZ1"));
+        assert_eq!("%+1", percent_decode("%+1"));
+    }
+
+    #[test]
+    fn test_route() {
+        assert_eq!(
+            Endpoint::Contextuals("chr1:2:C:G".to_string()),
+            route(&Method::Get, "/vcf/chr1/2/C/G/contextuals")
+        );
+        assert_eq!(
+            Endpoint::CanonicalRepresentative("chr1:1:C:G".to_string()),
+            route(&Method::Get, "/spdi/chr1%3A1%3AC%3AG/canonical_representative")
+        );
+        assert_eq!(Endpoint::Batch, route(&Method::Post, "/vcf/contextuals"));
+        assert_eq!(Endpoint::Batch, route(&Method::Post, "/spdi/canonical_representative/"));
+        assert_eq!(Endpoint::NotAllowed, route(&Method::Post, "/vcf/chr1/2/C/G/contextuals"));
+        assert_eq!(Endpoint::NotAllowed, route(&Method::Get, "/vcf/contextuals"));
+        assert_eq!(Endpoint::NotAllowed, route(&Method::Delete, "/spdi/chr1:1:C:G/canonical_representative"));
+        assert_eq!(Endpoint::NotFound, route(&Method::Get, "/"));
+        assert_eq!(Endpoint::NotFound, route(&Method::Get, "/vcf/chr1/2/C/contextuals"));
+        assert_eq!(Endpoint::NotFound, route(&Method::Get, "/spdi/chr1:1:C:G"));
+    }
+
+    #[test]
+    fn test_get_error_reply() {
+        let (status, json) = get_error_reply(404, "Not found: /");
+        assert_eq!(404, status);
+        assert_eq!(
+            serde_json::json!({"error": {"code": 404, "message": "Not found: /"}}),
+            json
+        );
+    }
+}
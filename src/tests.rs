@@ -10,7 +10,7 @@ fn get_spdi_string_del() {
     use std::path::PathBuf;
 
     let twobit_fname = std::env::var("TWOBIT_FNAME").unwrap().to_string();
-    let mut spdi = SPDI::new(&PathBuf::from(twobit_fname)).unwrap();
+    let spdi = SPDI::new(&PathBuf::from(twobit_fname)).unwrap();
     assert_eq!(
         "chr1:141454:TTATTATTATTATT:TTATTATTATT".to_string(),
         spdi.get_spdi_string("chr1".as_bytes(), 141457, "TTA".as_bytes(), "".as_bytes()).unwrap()
@@ -27,7 +27,7 @@ fn get_spdi_string_ins() {
     use std::path::PathBuf;
 
     let twobit_fname = std::env::var("TWOBIT_FNAME").unwrap().to_string();
-    let mut spdi = SPDI::new(&PathBuf::from(twobit_fname)).unwrap();
+    let spdi = SPDI::new(&PathBuf::from(twobit_fname)).unwrap();
     assert_eq!(
         "chr1:141454:TTATTATTATTATT:TTATTATTATTATTATT".to_string(),
         spdi.get_spdi_string("chr1".as_bytes(), 141458, "".as_bytes(), "TAT".as_bytes()).unwrap()
@@ -48,7 +48,7 @@ fn get_spdi_string_snv() {
     use std::path::PathBuf;
 
     let twobit_fname = std::env::var("TWOBIT_FNAME").unwrap().to_string();
-    let mut spdi = SPDI::new(&PathBuf::from(twobit_fname)).unwrap();
+    let spdi = SPDI::new(&PathBuf::from(twobit_fname)).unwrap();
     assert_eq!(
         "chr1:141453:G:C".to_string(),
         spdi.get_spdi_string("chr1".as_bytes(), 141453, "G".as_bytes(), "C".as_bytes()).unwrap()
@@ -105,7 +105,7 @@ fn grow_left() {
     // TTA(TTA)TTA
     //     ---
     let twobit_fname = std::env::var("TWOBIT_FNAME").unwrap().to_string();
-    let mut spdi = SPDI::new(&PathBuf::from(twobit_fname)).unwrap();
+    let spdi = SPDI::new(&PathBuf::from(twobit_fname)).unwrap();
     let ref_bases: Box<[Base]> = get_bases_of_string("TTA").unwrap();
     assert_eq!(
         (141454, get_bases_of_string("TTA").unwrap()),
@@ -123,7 +123,7 @@ fn grow_right() {
     // TTA(TTA)TTA
     //     ---
     let twobit_fname = std::env::var("TWOBIT_FNAME").unwrap().to_string();
-    let mut spdi = SPDI::new(&PathBuf::from(twobit_fname)).unwrap();
+    let spdi = SPDI::new(&PathBuf::from(twobit_fname)).unwrap();
     let bases: Box<[Base]> = get_bases_of_string("TTA").unwrap();
     assert_eq!(
         (141468, get_bases_of_string("TTATTATTATT").unwrap()),
@@ -139,7 +139,7 @@ fn grow() {
     use std::path::PathBuf;
 
     let twobit_fname = std::env::var("TWOBIT_FNAME").unwrap().to_string();
-    let mut spdi = SPDI::new(&PathBuf::from(twobit_fname)).unwrap();
+    let spdi = SPDI::new(&PathBuf::from(twobit_fname)).unwrap();
     // TTA(TTA)TTA
     //     ---
     let ref_bases: Box<[Base]> = get_bases_of_string("TTA").unwrap();
@@ -178,15 +178,15 @@ fn long_conversion() {
 
 
     let twobit_fname = std::env::var("TWOBIT_FNAME").unwrap().to_string();
-    let mut spdi = SPDI::new(&PathBuf::from(&twobit_fname)).unwrap();
+    let spdi = SPDI::new(&PathBuf::from(&twobit_fname)).unwrap();
     let ref_bases: Box<[Base]> = get_bases_of_string("GATTC").unwrap();
     let alt_bases: Box<[Base]> = get_bases_of_string("GATTCTATTC").unwrap();
     let result = spdi.get_spdi_conversion("chr22".as_bytes(), 45795354, &ref_bases, &alt_bases)
             .unwrap();
     println!("converted ref_base_len={} alt_base_len={} total={}", result.1.len(), result.2.len(), result.1.len() + result.2.len());
     println!("orig ref_base_len={} alt_base_len={} total={}", ref_bases.len(), alt_bases.len(), ref_bases.len() + alt_bases.len());
-    println!("converted ref_bases={}", result.1.iter().map(|b| get_char_of_base(b)).collect::<String>());
-    println!("converted alt_bases={}", result.2.iter().map(|b| get_char_of_base(b)).collect::<String>());
+    println!("converted ref_bases={}", result.1.iter().map(get_char_of_base).collect::<String>());
+    println!("converted alt_bases={}", result.2.iter().map(get_char_of_base).collect::<String>());
     assert_eq!(
         (
             45795355,
@@ -196,3 +196,465 @@ fn long_conversion() {
         result
     );
 }
+
+/// Writes a small 2bit file holding `seqs` and returns its path. `N` bases are
+/// stored as hard-masked blocks, as in UCSC 2bit files. Soft masks are not
+/// written, so lower case bases read back in upper case.
+pub(crate) fn write_twobit(name: &str, seqs: &[(&str, &str)]) -> std::path::PathBuf {
+    let mut header: Vec<u8> = Vec::new();
+    header.extend(0x1A412743u32.to_le_bytes());
+    header.extend(0u32.to_le_bytes());
+    header.extend((seqs.len() as u32).to_le_bytes());
+    header.extend(0u32.to_le_bytes());
+    let index_len: usize = seqs.iter().map(|(chrom, _)| 1 + chrom.len() + 4).sum();
+    let mut records: Vec<u8> = Vec::new();
+    for (chrom, seq) in seqs {
+        let offset = 16 + index_len + records.len();
+        header.push(chrom.len() as u8);
+        header.extend(chrom.as_bytes());
+        header.extend((offset as u32).to_le_bytes());
+        let mut n_blocks: Vec<(u32, u32)> = Vec::new();
+        for (i, c) in seq.bytes().enumerate() {
+            if c == b'N' {
+                match n_blocks.last_mut() {
+                    Some((start, size)) if (*start + *size) as usize == i => *size += 1,
+                    _ => n_blocks.push((i as u32, 1)),
+                }
+            }
+        }
+        records.extend((seq.len() as u32).to_le_bytes());
+        records.extend((n_blocks.len() as u32).to_le_bytes());
+        for (start, _) in n_blocks.iter() {
+            records.extend(start.to_le_bytes());
+        }
+        for (_, size) in n_blocks.iter() {
+            records.extend(size.to_le_bytes());
+        }
+        records.extend(0u32.to_le_bytes());
+        records.extend(0u32.to_le_bytes());
+        for chunk in seq.as_bytes().chunks(4) {
+            let mut byte: u8 = 0;
+            for i in 0..4 {
+                let code: u8 = match chunk.get(i).map(u8::to_ascii_uppercase) {
+                    Some(b'C') => 1,
+                    Some(b'A') => 2,
+                    Some(b'G') => 3,
+                    _ => 0,
+                };
+                byte |= code << (6 - 2 * i);
+            }
+            records.push(byte);
+        }
+    }
+    header.extend(records);
+    let path = std::env::temp_dir().join(format!("spdi-test-{}-{}.2bit", std::process::id(), name));
+    std::fs::write(&path, header).unwrap();
+    path
+}
+
+#[test]
+fn shared_across_threads() {
+    use crate::SPDI;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<SPDI>();
+
+    // TTA(TTA)TTA
+    let path = write_twobit("threads", &[("chrT", "GCGTTATTATTAGCG")]);
+    let spdi = SPDI::new(&path).unwrap();
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            let spdi = &spdi;
+            s.spawn(move || {
+                assert_eq!(
+                    "chrT:4:TTATTATTA:TTATTA".to_string(),
+                    spdi.get_spdi_string("chrT".as_bytes(), 7, "TTA".as_bytes(), "".as_bytes()).unwrap()
+                );
+            });
+        }
+    });
+    let cloned = spdi.clone();
+    assert_eq!(
+        "chrT:4:TTATTATTA:TTATTATTATTA".to_string(),
+        cloned.get_spdi_string("chrT".as_bytes(), 4, "".as_bytes(), "TTA".as_bytes()).unwrap()
+    );
+}
+
+#[test]
+fn fasta_reference() {
+    use crate::SPDI;
+
+    let seqs = [("chrT", "GCGTTATTATTAGCG"), ("chrA", "ACGTNNACGT")];
+    let twobit = SPDI::new(&write_twobit("fasta", &seqs)).unwrap();
+    let path = std::env::temp_dir().join(format!("spdi-test-{}-fasta.fa", std::process::id()));
+    std::fs::write(&path, ">chrT test\ngcgTTATTA\nTTAGCG\n\n>chrA\nACGTNN\nACGT\n").unwrap();
+    let fasta = SPDI::new(&path).unwrap();
+    assert_eq!(twobit.sequence_names(), fasta.sequence_names());
+    assert_eq!(Some(15), fasta.sequence_length("chrT"));
+    assert_eq!(twobit.sequence_md5("chrA").unwrap(), fasta.sequence_md5("chrA").unwrap());
+    assert_eq!(
+        twobit.get_spdi_string("chrT".as_bytes(), 7, "TTA".as_bytes(), "".as_bytes()).unwrap(),
+        fasta.get_spdi_string("chrT".as_bytes(), 7, "TTA".as_bytes(), "".as_bytes()).unwrap()
+    );
+    assert!(fasta.get_spdi_string("chrX".as_bytes(), 1, "A".as_bytes(), "".as_bytes()).is_err());
+}
+
+#[test]
+fn c_api() {
+    use crate::capi::*;
+    use std::ffi::{CStr, CString};
+
+    let path = write_twobit("c_api", &[("chrT", "GCGTTATTATTAGCG")]);
+    let path = CString::new(path.to_str().unwrap()).unwrap();
+    let c = |s: &str| CString::new(s).unwrap();
+    unsafe {
+        assert!(spdi_reference_open(c("/no/such/file").as_ptr()).is_null());
+        assert!(CStr::from_ptr(spdi_last_error()).to_str().unwrap().contains("/no/such/file"));
+        let reference = spdi_reference_open(path.as_ptr());
+        assert!(!reference.is_null());
+        let spdi = spdi_convert(reference, c("chrT").as_ptr(), 7, c("TTA").as_ptr(), c("-").as_ptr());
+        assert_eq!("chrT:4:TTATTATTA:TTATTA", CStr::from_ptr(spdi).to_str().unwrap());
+        spdi_string_free(spdi);
+        let spdi = spdi_convert(reference, c("chrX").as_ptr(), 7, c("TTA").as_ptr(), c("").as_ptr());
+        assert!(spdi.is_null());
+        assert_eq!(
+            "Sequence not in the reference: chrX",
+            CStr::from_ptr(spdi_last_error()).to_str().unwrap()
+        );
+        assert!(spdi_convert(reference, std::ptr::null(), 7, c("TTA").as_ptr(), c("").as_ptr()).is_null());
+        for alt in ["-A", "--"] {
+            assert!(spdi_convert(reference, c("chrT").as_ptr(), 7, c("TTA").as_ptr(), c(alt).as_ptr()).is_null());
+            assert_eq!("Wrong base: -", CStr::from_ptr(spdi_last_error()).to_str().unwrap());
+        }
+        spdi_reference_free(reference);
+    }
+}
+
+#[test]
+fn sequence_info() {
+    use crate::SPDI;
+
+    let path = write_twobit("sequence_info", &[("chrA", "ACGTNNacgt"), ("chrB", "TTTT")]);
+    let spdi = SPDI::new(&path).unwrap();
+    assert_eq!(vec!["chrA".to_string(), "chrB".to_string()], spdi.sequence_names());
+    assert_eq!(Some(10), spdi.sequence_length("chrA"));
+    assert_eq!(None, spdi.sequence_length("chrC"));
+    // Soft-masked bases are upper-cased before hashing.
+    assert_eq!(
+        format!("{:x}", md5::compute("ACGTNNACGT")),
+        spdi.sequence_md5("chrA").unwrap()
+    );
+    assert!(spdi.sequence_md5("chrC").is_err());
+}
+
+#[test]
+fn circular() {
+    use crate::SPDI;
+
+    // A-run across the origin: positions 11, 12, 1, 2 and 3.
+    let path = write_twobit("circular", &[("chrC", "AAACGTGCTCAA")]);
+    let mut spdi = SPDI::new(&path).unwrap();
+    spdi.set_circular("chrC", true);
+    assert!(spdi.is_circular("chrC"));
+    assert_eq!(
+        "chrC:11:AAAAA:AAAA".to_string(),
+        spdi.get_spdi_string("chrC".as_bytes(), 2, "A".as_bytes(), "".as_bytes()).unwrap()
+    );
+    assert_eq!(
+        "chrC:11:AAAAA:AAAA".to_string(),
+        spdi.get_spdi_string("chrC".as_bytes(), 11, "A".as_bytes(), "".as_bytes()).unwrap()
+    );
+    assert_eq!(
+        "chrC:11:AAAAA:AAAAAA".to_string(),
+        spdi.get_spdi_string("chrC".as_bytes(), 1, "".as_bytes(), "A".as_bytes()).unwrap()
+    );
+    // Substitutions and unchanged bases given past the end are wrapped too.
+    assert_eq!(
+        "chrC:1:A:C".to_string(),
+        spdi.get_spdi_string("chrC".as_bytes(), 13, "A".as_bytes(), "C".as_bytes()).unwrap()
+    );
+    assert_eq!(
+        "chrC:1:A:A".to_string(),
+        spdi.get_spdi_string("chrC".as_bytes(), 13, "A".as_bytes(), "A".as_bytes()).unwrap()
+    );
+    // A trimmed substitution is wrapped at its first changed base.
+    assert_eq!(
+        "chrC:1:A:C".to_string(),
+        spdi.get_spdi_string("chrC".as_bytes(), 12, "AA".as_bytes(), "AC".as_bytes()).unwrap()
+    );
+}
+
+#[test]
+fn bounds() {
+    use crate::error::Error;
+    use crate::SPDI;
+
+    // TT(A)AA(GGG)
+    let path = write_twobit("bounds", &[("chrB", "TTAAAGGG")]);
+    let spdi = SPDI::new(&path).unwrap();
+    assert!(matches!(
+        spdi.get_spdi_string("chrZ".as_bytes(), 1, "T".as_bytes(), "C".as_bytes()),
+        Err(Error::UnknownSequence { name }) if name == "chrZ"
+    ));
+    assert!(matches!(
+        spdi.get_spdi_string("chrB".as_bytes(), 0, "".as_bytes(), "T".as_bytes()),
+        Err(Error::OutOfBounds { pos: 0, len: 0, .. })
+    ));
+    assert!(matches!(
+        spdi.get_spdi_string("chrB".as_bytes(), 7, "GGG".as_bytes(), "G".as_bytes()),
+        Err(Error::OutOfBounds { pos: 7, len: 3, .. })
+    ));
+    // Growth stops at both ends of the sequence.
+    assert_eq!(
+        "chrB:1:TT:T".to_string(),
+        spdi.get_spdi_string("chrB".as_bytes(), 2, "T".as_bytes(), "".as_bytes()).unwrap()
+    );
+    assert_eq!(
+        "chrB:6:GGG:GG".to_string(),
+        spdi.get_spdi_string("chrB".as_bytes(), 7, "G".as_bytes(), "".as_bytes()).unwrap()
+    );
+    assert_eq!(
+        "chrB:6:GGG:GGGG".to_string(),
+        spdi.get_spdi_string("chrB".as_bytes(), 9, "".as_bytes(), "G".as_bytes()).unwrap()
+    );
+}
+
+#[test]
+fn substitution_position() {
+    use crate::SPDI;
+
+    let path = write_twobit("substitution", &[("chrS", "TTAAAGGG")]);
+    let spdi = SPDI::new(&path).unwrap();
+    // Bases shared at either end are trimmed, and the position moves with them.
+    assert_eq!(
+        "chrS:3:A:G".to_string(),
+        spdi.get_spdi_string("chrS".as_bytes(), 2, "TA".as_bytes(), "TG".as_bytes()).unwrap()
+    );
+    assert_eq!(
+        "chrS:4:AA:GC".to_string(),
+        spdi.get_spdi_string("chrS".as_bytes(), 3, "AAAG".as_bytes(), "AGCG".as_bytes()).unwrap()
+    );
+}
+
+#[test]
+fn variant_class() {
+    use crate::{VariantClass, SPDI};
+
+    // TT(A)AA(GGG)CT
+    let path = write_twobit("class", &[("chrK", "TTAAAGGGCT")]);
+    let spdi = SPDI::new(&path).unwrap();
+    let classify = |pos: usize, ref_bases: &str, alt_bases: &str| {
+        let v = spdi
+            .get_classified_spdi_str("chrK".as_bytes(), pos, ref_bases.as_bytes(), alt_bases.as_bytes())
+            .unwrap();
+        (v.class, v.shiftable)
+    };
+    assert_eq!((VariantClass::Identity, false), classify(2, "TA", "TA"));
+    assert_eq!((VariantClass::Snv, false), classify(2, "TA", "TG"));
+    assert_eq!((VariantClass::Mnv, false), classify(3, "AAAG", "AGCG"));
+    assert_eq!((VariantClass::Delins, false), classify(9, "C", "GA"));
+    assert_eq!((VariantClass::Deletion, true), classify(2, "TA", "T"));
+    assert_eq!((VariantClass::Deletion, false), classify(8, "GC", "G"));
+    assert_eq!((VariantClass::Insertion, true), classify(6, "", "G"));
+    assert_eq!((VariantClass::Insertion, false), classify(3, "", "C"));
+    let v = spdi.get_classified_spdi_str("chrK".as_bytes(), 2, "TA".as_bytes(), "T".as_bytes()).unwrap();
+    assert_eq!((3, "AAA", "AA"), (v.pos, v.ref_bases.as_str(), v.alt_bases.as_str()));
+    assert_eq!("deletion", VariantClass::Deletion.to_string());
+}
+
+#[test]
+fn tandem_repeat() {
+    use crate::SPDI;
+
+    // GCG(TTA)3GCG
+    let path = write_twobit("repeat", &[("chrT", "GCGTTATTATTAGCG")]);
+    let spdi = SPDI::new(&path).unwrap();
+    let get_repeat = |pos: usize, ref_bases: &str, alt_bases: &str| {
+        spdi.get_classified_spdi_str("chrT".as_bytes(), pos, ref_bases.as_bytes(), alt_bases.as_bytes())
+            .unwrap()
+            .repeat
+            .map(|v| (v.motif, v.ref_copies, v.alt_copies))
+    };
+    assert_eq!(Some(("TTA".to_string(), 3, 2)), get_repeat(7, "TTA", ""));
+    assert_eq!(Some(("TTA".to_string(), 3, 5)), get_repeat(9, "A", "ATTATTA"));
+    // The motif starts where the repeat starts.
+    assert_eq!(Some(("TTA".to_string(), 3, 4)), get_repeat(5, "T", "TATT"));
+    assert_eq!(None, get_repeat(2, "C", "CA"));
+    assert_eq!(None, get_repeat(4, "T", "C"));
+}
+
+#[test]
+fn compact_spdi() {
+    use crate::{CompactSequence, SPDI};
+
+    // GCG(TTA)3GCG, and an A-run across the origin of chrC.
+    let path = write_twobit("compact", &[("chrT", "GCGTTATTATTAGCG"), ("chrC", "AAACGTGCTCAA")]);
+    let mut spdi = SPDI::new(&path).unwrap();
+    spdi.set_circular("chrC", true);
+    let compact = spdi.get_compact_spdi_str("chrT".as_bytes(), 9, "A".as_bytes(), "ATTATTA".as_bytes()).unwrap();
+    assert_eq!(4, compact.pos);
+    assert_eq!(CompactSequence::Repeat { motif: "TTA".to_string(), len: 9 }, compact.deleted);
+    assert_eq!(Some(5), compact.inserted.copies());
+    assert_eq!(
+        ("TTATTATTA".to_string(), "TTATTATTATTATTA".to_string()),
+        compact.expand()
+    );
+    let compact = spdi.get_compact_spdi_str("chrT".as_bytes(), 2, "CG".as_bytes(), "TG".as_bytes()).unwrap();
+    assert_eq!(CompactSequence::Literal("C".to_string()), compact.deleted);
+    assert_eq!(None, CompactSequence::Repeat { motif: String::new(), len: 3 }.copies());
+    // Compact SPDIs expand to the SPDIs of all indels of up to 3 bases.
+    for (chrom, len) in [("chrT", 15), ("chrC", 12)] {
+        for pos in 1..len {
+            for indel_len in 1..=3 {
+                let inserted: String = "TTAC".chars().cycle().skip(pos % 4).take(indel_len).collect();
+                let deleted = spdi.get_reference_bases(chrom, pos, indel_len.min(len - pos + 1)).unwrap();
+                for (ref_bases, alt_bases) in [("", inserted.as_str()), (deleted.as_str(), "")] {
+                    let expected = spdi.get_spdi_string_components_str(
+                        chrom.as_bytes(), pos, ref_bases.as_bytes(), alt_bases.as_bytes());
+                    let compact = spdi.get_compact_spdi_str(
+                        chrom.as_bytes(), pos, ref_bases.as_bytes(), alt_bases.as_bytes());
+                    assert_eq!(
+                        expected.unwrap(),
+                        compact.map(|v| (v.pos, v.expand().0, v.expand().1)).unwrap(),
+                        "{}:{}:{}:{}", chrom, pos, ref_bases, alt_bases
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn normalization_shifts() {
+    use crate::{NormalizationShifts, SPDI};
+
+    // TT(A)AA(GGG), and an A-run across the origin of chrC.
+    let path = write_twobit("shifts", &[("chrB", "TTAAAGGG"), ("chrC", "AAACGTGCTCAA")]);
+    let mut spdi = SPDI::new(&path).unwrap();
+    spdi.set_circular("chrC", true);
+    let get_shifts = |chrom: &str, pos: usize, ref_bases: &str, alt_bases: &str| {
+        let v = spdi
+            .get_classified_spdi_str(chrom.as_bytes(), pos, ref_bases.as_bytes(), alt_bases.as_bytes())
+            .unwrap();
+        let s = v.shifts;
+        (s.trimmed_left, s.trimmed_right, s.grown_left, s.grown_right)
+    };
+    assert_eq!((0, 1, 1, 1), get_shifts("chrB", 4, "AA", "A"));
+    assert_eq!((1, 0, 0, 2), get_shifts("chrB", 2, "TA", "T"));
+    assert_eq!((1, 0, 0, 0), get_shifts("chrB", 2, "TA", "TG"));
+    assert_eq!((0, 0, 0, 3), get_shifts("chrB", 3, "", "A"));
+    assert_eq!((0, 0, 0, 0), get_shifts("chrB", 3, "", "C"));
+    // chrC:11:AAAAA:AAAA
+    assert_eq!((0, 0, 3, 1), get_shifts("chrC", 2, "A", ""));
+    assert_eq!(NormalizationShifts::default(), spdi.get_classified_spdi_str(
+        "chrB".as_bytes(), 1, "T".as_bytes(), "C".as_bytes()).unwrap().shifts);
+}
+
+#[test]
+fn reference_bases() {
+    use crate::error::Error;
+    use crate::SPDI;
+
+    let path = write_twobit("reference_bases", &[("chrR", "TTAANGGG")]);
+    let spdi = SPDI::new(&path).unwrap();
+    assert_eq!(spdi.get_reference_bases("chrR", 2, 3).unwrap(), "TAA");
+    assert_eq!(spdi.get_reference_bases("chrR", 5, 0).unwrap(), "");
+    assert!(matches!(
+        spdi.get_reference_bases("chrR", 7, 3),
+        Err(Error::OutOfBounds { pos: 7, len: 3, .. })
+    ));
+    assert!(spdi.check_reference(b"chrR", 2, b"taa").is_ok());
+    assert!(spdi.check_reference(b"chrR", 4, b"ACG").is_ok());
+    assert!(spdi.check_reference(b"chrR", 1, b"TNA").is_ok());
+    assert!(matches!(
+        spdi.check_reference(b"chrR", 1, b"TTC"),
+        Err(Error::ReferenceMismatch { pos: 1, reference, .. }) if reference == "TTA"
+    ));
+}
+
+#[test]
+fn no_panic_on_arbitrary_input() {
+    use crate::util::get_bases_of_vu8;
+    use crate::SPDI;
+
+    // xorshift64, so that failures are reproducible
+    let mut state: u64 = 0x9E3779B97F4A7C15;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let path = write_twobit(
+        "no_panic",
+        &[("chrR", "ACACACACGTTTTTTTNNNNGAGAGAGATTCTATTCTATTCTAC"), ("chrS", "AAAA")],
+    );
+    let mut spdi = SPDI::new(&path).unwrap();
+    spdi.set_circular("chrS", true);
+    let chroms: [&[u8]; 6] = [b"chrR", b"chrS", b"chrX", b"", b"\xff\xfe", b"chrR\0"];
+    let alphabet: &[u8] = b"ACGTNacgtn.-*<>X,";
+    let random_bases = |next: &mut dyn FnMut() -> u64| -> Vec<u8> {
+        let len = (next() % 8) as usize;
+        (0..len)
+            .map(|_| match next() % 4 {
+                0 => next() as u8,
+                _ => alphabet[(next() % alphabet.len() as u64) as usize],
+            })
+            .collect()
+    };
+    for _ in 0..20000 {
+        let chrom = chroms[(next() % chroms.len() as u64) as usize];
+        let pos: usize = match next() % 5 {
+            0 => 0,
+            1 => usize::MAX - (next() % 4) as usize,
+            _ => (next() % 50) as usize,
+        };
+        let ref_bases = random_bases(&mut next);
+        let alt_bases = random_bases(&mut next);
+        let _ = spdi.get_spdi_string(chrom, pos, &ref_bases, &alt_bases);
+        let _ = spdi.get_spdi_conversion_str(chrom, pos, &ref_bases, &alt_bases);
+        let _ = spdi.get_spdi_string_components_str(chrom, pos, &ref_bases, &alt_bases);
+        if let (Ok(r), Ok(a)) = (get_bases_of_vu8(&ref_bases), get_bases_of_vu8(&alt_bases)) {
+            let _ = spdi.get_spdi_conversion(chrom, pos, &r, &a);
+        }
+    }
+    assert!(spdi.get_spdi_string(b"chrR", 1, b"", b"").is_err());
+    assert!(spdi.get_spdi_conversion_str(b"chrR", 1, b"A", b"").is_ok());
+}
+
+#[test]
+fn conversion_error() {
+    use crate::error::Error;
+    use std::error::Error as _;
+    let e = Error::Conversion {
+        chrom: "chr1".to_string(),
+        pos: 10,
+        ref_base: "A".to_string(),
+        alt_base: "AT".to_string(),
+        source: Box::new(Error::NoNonRepeat {
+            chrom: "chr1".to_string(),
+            search_len: 5,
+            search_start: 11,
+        }),
+    };
+    assert_eq!(
+        e.to_string(),
+        "Could not find a non-repeat sequence after 5 bases from chr1:11. chr1:10:A:AT"
+    );
+    assert_eq!(e.kind(), "NoNonRepeat");
+    assert!(matches!(
+        e.source().and_then(|s| s.downcast_ref::<Error>()),
+        Some(Error::NoNonRepeat { .. })
+    ));
+}
+
+#[test]
+fn invalid_base_message() {
+    use crate::error::Error;
+    use crate::util::get_bases_of_vu8;
+
+    match get_bases_of_vu8(b"AC*") {
+        Err(e @ Error::InvalidBase { .. }) => assert_eq!(e.to_string(), "Wrong base: *"),
+        v => panic!("unexpected result: {:?}", v),
+    }
+}
@@ -7,6 +7,14 @@
 use crate::error::Error;
 use noodles::vcf::record::reference_bases::Base;
 
+/// Returns a sequence name as `&str`. Names which are not UTF-8 cannot be in
+/// the reference, so they are reported as unknown sequences.
+pub fn get_chrom_str(chrom: &[u8]) -> Result<&str, Error> {
+    std::str::from_utf8(chrom).map_err(|_| Error::UnknownSequence {
+        name: String::from_utf8_lossy(chrom).to_string(),
+    })
+}
+
 pub fn get_bases_of_vu8(s: &[u8]) -> Result<Vec<Base>, Error> {
     let mut bases: Vec<Base> = Vec::with_capacity(s.len());
     for c in s.iter() {
@@ -14,7 +22,7 @@ pub fn get_bases_of_vu8(s: &[u8]) -> Result<Vec<Base>, Error> {
             Some(base) => bases.push(base),
             None => {
                 return Err(Error::InvalidBase {
-                    base: c.to_string(),
+                    base: (*c as char).to_string(),
                 });
             }
         }
@@ -65,34 +73,10 @@ pub fn get_string_of_bases(bases: &[Base]) -> String {
 
 pub fn is_base_same_as_char(base: &Base, c: char) -> bool {
     match base {
-        Base::A => {
-            if c == 'A' {
-                true
-            } else {
-                false
-            }
-        }
-        Base::T => {
-            if c == 'T' {
-                true
-            } else {
-                false
-            }
-        }
-        Base::G => {
-            if c == 'G' {
-                true
-            } else {
-                false
-            }
-        }
-        Base::C => {
-            if c == 'C' {
-                true
-            } else {
-                false
-            }
-        }
+        Base::A => c == 'A',
+        Base::T => c == 'T',
+        Base::G => c == 'G',
+        Base::C => c == 'C',
         Base::N => true,
     }
 }
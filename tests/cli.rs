@@ -0,0 +1,481 @@
+// Copyright Ryangguk Kim @ Oak Bioinformatics, LLC
+//
+// This software is available under a dual licensing model, offering users the choice between the Affero General Public License version 3 (AGPL-3) for open-source use and a commercial license for proprietary or commercial use.
+//
+// To obtain a commercial license, please contact info@oakbioinformatics.com.
+
+//! Tests of the spdi command on small files.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Writes a small 2bit file holding `seqs` without N blocks or masks and
+/// returns its path.
+fn write_twobit(name: &str, seqs: &[(&str, &str)]) -> PathBuf {
+    let mut header: Vec<u8> = Vec::new();
+    header.extend(0x1A412743u32.to_le_bytes());
+    header.extend(0u32.to_le_bytes());
+    header.extend((seqs.len() as u32).to_le_bytes());
+    header.extend(0u32.to_le_bytes());
+    let index_len: usize = seqs.iter().map(|(chrom, _)| 1 + chrom.len() + 4).sum();
+    let mut records: Vec<u8> = Vec::new();
+    for (chrom, seq) in seqs {
+        let offset = header.len() + index_len + records.len();
+        header.push(chrom.len() as u8);
+        header.extend(chrom.as_bytes());
+        header.extend((offset as u32).to_le_bytes());
+        records.extend((seq.len() as u32).to_le_bytes());
+        records.extend([0u8; 12]);
+        for chunk in seq.as_bytes().chunks(4) {
+            let mut byte: u8 = 0;
+            for (i, c) in chunk.iter().enumerate() {
+                let code: u8 = match c {
+                    b'C' => 1,
+                    b'A' => 2,
+                    b'G' => 3,
+                    _ => 0,
+                };
+                byte |= code << (6 - 2 * i);
+            }
+            records.push(byte);
+        }
+    }
+    header.extend(records);
+    let path = std::env::temp_dir().join(format!("spdi-cli-{}-{}.2bit", std::process::id(), name));
+    std::fs::write(&path, header).unwrap();
+    path
+}
+
+/// Writes a file under the temporary directory and returns its path.
+fn write_file(name: &str, content: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("spdi-cli-{}-{}", std::process::id(), name));
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+/// Writes a VCF file with the given header lines and records.
+fn write_vcf(name: &str, header: &[&str], records: &[&str]) -> PathBuf {
+    let mut content = String::from("##fileformat=VCFv4.2\n");
+    for line in header {
+        content.push_str(line);
+        content.push('\n');
+    }
+    content.push_str("#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n");
+    for record in records {
+        content.push_str(record);
+        content.push('\n');
+    }
+    write_file(&format!("{}.vcf", name), &content)
+}
+
+fn spdi(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_spdi")).args(args).output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+/// Returns the output lines which start with `prefix`.
+fn lines_with<'a>(text: &'a str, prefix: &str) -> Vec<&'a str> {
+    text.lines().filter(|line| line.starts_with(prefix)).collect()
+}
+
+/// TTA(TTA)TTA between GCGs
+fn reference(name: &str) -> String {
+    write_twobit(name, &[("chr1", "GCGTTATTATTAGCG")]).to_string_lossy().to_string()
+}
+
+#[test]
+fn reannotate_with_fewer_fields() {
+    let twobit = reference("reannotate");
+    let vcf = write_vcf("reannotate", &[], &["1\t6\t.\tATTA\tA\t.\t.\tDP=3"]);
+    let first = spdi(&["annotate-vcf", "-t", &twobit, "-f", vcf.to_str().unwrap(), "--split-fields", "--class-fields", "--repeat-fields", "--shift-fields"]);
+    assert!(first.status.success());
+    assert_eq!(12, lines_with(&stdout(&first), "##INFO=<ID=OV_SPDI_IDS").len());
+    assert!(stdout(&first).contains(";OV_SPDI_IDS_CLASS=deletion;OV_SPDI_IDS_SHIFTABLE=1"));
+    assert!(stdout(&first).contains(";OV_SPDI_IDS_MOTIF=TTA;OV_SPDI_IDS_PERIOD=3;OV_SPDI_IDS_REF_COPIES=3;OV_SPDI_IDS_ALT_COPIES=2"));
+    assert!(stdout(&first).contains(";OV_SPDI_IDS_LEFT_SHIFT=2;OV_SPDI_IDS_RIGHT_SHIFT=4"));
+    let annotated = write_file("reannotate.annotated.vcf", &stdout(&first));
+    let second = spdi(&["annotate-vcf", "-t", &twobit, "-f", annotated.to_str().unwrap()]);
+    assert!(second.status.success());
+    let out = stdout(&second);
+    assert_eq!(
+        vec!["##INFO=<ID=OV_SPDI_IDS,Number=A,Type=String,Description=\"SPDI notation of each alternate allele\">"],
+        lines_with(&out, "##INFO=")
+    );
+    assert_eq!(
+        vec!["1\t6\t.\tATTA\tA\t.\t.\tDP=3;OV_SPDI_IDS=chr1:4:TTATTATTA:TTATTA"],
+        lines_with(&out, "1\t")
+    );
+}
+
+#[test]
+fn skip_existing_annotations() {
+    let twobit = reference("skip");
+    let vcf = write_vcf(
+        "skip",
+        &["##INFO=<ID=SPDI,Number=A,Type=String,Description=\"Earlier SPDIs\">"],
+        &["1\t6\t.\tATTA\tA\t.\t.\tSPDI=earlier", "1\t2\t.\tC\tG\t.\t.\t."],
+    );
+    let output = spdi(&["annotate-vcf", "-t", &twobit, "-f", vcf.to_str().unwrap(), "--info-key", "SPDI", "--existing", "skip"]);
+    assert!(output.status.success());
+    let out = stdout(&output);
+    assert_eq!(1, lines_with(&out, "##INFO=<ID=SPDI,").len());
+    assert_eq!(
+        vec!["1\t6\t.\tATTA\tA\t.\t.\tSPDI=earlier", "1\t2\t.\tC\tG\t.\t.\tSPDI=chr1:2:C:G"],
+        lines_with(&out, "1\t")
+    );
+    let output = spdi(&["annotate-vcf", "-t", &twobit, "-f", vcf.to_str().unwrap(), "--info-key", "SPDI", "--existing", "fail"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn provenance() {
+    let twobit = reference("provenance");
+    let vcf = write_vcf(
+        "provenance",
+        &["##reference=file:///data/caller.fa"],
+        &["1\t2\t.\tC\tG\t.\t.\t."],
+    );
+    let version = format!("##spdiVersion={}", env!("CARGO_PKG_VERSION"));
+    let output = spdi(&["annotate-vcf", "-t", &twobit, "-f", vcf.to_str().unwrap()]);
+    let out = stdout(&output);
+    assert_eq!(vec![version.as_str()], lines_with(&out, "##spdiVersion="));
+    assert_eq!(vec!["##reference=file:///data/caller.fa"], lines_with(&out, "##reference="));
+    assert_eq!(1, lines_with(&out, "##spdiReference=file://").len());
+    assert!(lines_with(&out, "##spdiReferenceSequence=").is_empty());
+    // A re-run replaces the lines of the earlier run and keeps those of the caller.
+    let annotated = write_file("provenance.annotated.vcf", &out);
+    for existing in ["overwrite", "skip"] {
+        let output = spdi(&[
+            "annotate-vcf", "-t", &twobit, "-f", annotated.to_str().unwrap(), "--existing", existing, "--reference-checksums",
+        ]);
+        let out = stdout(&output);
+        assert_eq!(vec![version.as_str()], lines_with(&out, "##spdiVersion="));
+        assert_eq!(vec!["##reference=file:///data/caller.fa"], lines_with(&out, "##reference="));
+        assert_eq!(1, lines_with(&out, "##spdiReference=file://").len());
+    }
+    let output = spdi(&["annotate-vcf", "-t", &twobit, "-f", vcf.to_str().unwrap(), "--reference-checksums"]);
+    assert_eq!(
+        vec![format!(
+            "##spdiReferenceSequence=<ID=chr1,length=15,md5={:x}>",
+            md5::compute("GCGTTATTATTAGCG")
+        )],
+        lines_with(&stdout(&output), "##spdiReferenceSequence=")
+    );
+    let output = spdi(&["--version"]);
+    assert!(stdout(&output).contains(env!("CARGO_PKG_VERSION")));
+}
+
+#[test]
+fn contig_check() {
+    let twobit = reference("contig");
+    let vcf = write_vcf(
+        "contig",
+        &["##contig=<ID=1,length=16,assembly=GRCh37>"],
+        &["1\t2\t.\tC\tG\t.\t.\t."],
+    );
+    let output = spdi(&["annotate-vcf", "-t", &twobit, "-f", vcf.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("has length 16 in the VCF header"));
+    let output = spdi(&["annotate-vcf", "-t", &twobit, "-f", vcf.to_str().unwrap(), "--contig-check", "fail"]);
+    assert!(!output.status.success());
+    let output = spdi(&[
+        "annotate-vcf", "-t", &twobit, "-f", vcf.to_str().unwrap(), "--contig-check", "off", "--reference-assembly", "GRCh38",
+    ]);
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn contig_naming() {
+    let twobit = reference("naming");
+    let vcf = write_vcf("naming", &[], &["1\t2\t.\tC\tG\t.\t.\t."]);
+    let output = spdi(&["annotate-vcf", "-t", &twobit, "-f", vcf.to_str().unwrap(), "--contig-naming", "input"]);
+    assert!(output.status.success());
+    assert_eq!(vec!["1\t2\t.\tC\tG\t.\t.\tOV_SPDI_IDS=1:2:C:G"], lines_with(&stdout(&output), "1\t"));
+    // The small reference is not GRCh38, so its chr1 is not NC_000001.11.
+    let output = spdi(&["annotate-vcf", "-t", &twobit, "-f", vcf.to_str().unwrap(), "--contig-naming", "refseq"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("needs a GRCh38 reference"));
+}
+
+#[test]
+fn contig_aliases() {
+    let twobit = reference("aliases");
+    let vcf = write_vcf(
+        "aliases",
+        &[],
+        &["chr1\t2\t.\tC\tG\t.\t.\t.", "one\t2\t.\tC\tG\t.\t.\t.", "NC_000001.11\t2\t.\tC\tG\t.\t.\t."],
+    );
+    let aliases = write_file("aliases.txt", "# alias\tname\none\tchr1\n");
+    let output = spdi(&["annotate-vcf", "-t", &twobit, "-f", vcf.to_str().unwrap(), "--contig-aliases", aliases.to_str().unwrap()]);
+    assert!(output.status.success());
+    // The reference is not GRCh38, so a GRCh38 accession is not resolved.
+    assert_eq!(
+        vec![
+            "chr1\t2\t.\tC\tG\t.\t.\tOV_SPDI_IDS=chr1:2:C:G",
+            "one\t2\t.\tC\tG\t.\t.\tOV_SPDI_IDS=chr1:2:C:G",
+            "NC_000001.11\t2\t.\tC\tG\t.\t.\t.",
+        ],
+        stdout(&output).lines().filter(|line| !line.starts_with('#')).collect::<Vec<&str>>()
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Chromosome [NC_000001.11] not found in the reference"));
+}
+
+#[test]
+fn yoruba_mito() {
+    let seq = "A".repeat(16571);
+    let twobit = write_twobit("yoruba", &[("chrM", &seq)]);
+    let vcf = write_vcf("yoruba", &[], &["MT\t2\t.\tA\tG\t.\t.\t.", "NC_012920.1\t2\t.\tA\tG\t.\t.\t."]);
+    let output = spdi(&["annotate-vcf", "-t", twobit.to_str().unwrap(), "-f", vcf.to_str().unwrap()]);
+    assert!(output.status.success());
+    assert_eq!(
+        vec!["MT\t2\t.\tA\tG\t.\t.\tOV_SPDI_IDS=chrM:2:A:G", "NC_012920.1\t2\t.\tA\tG\t.\t.\t."],
+        stdout(&output).lines().filter(|line| !line.starts_with('#')).collect::<Vec<&str>>()
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    assert!(stderr.contains("[chrM] in the reference is the Yoruba mitochondrial sequence"));
+    assert!(stderr.contains("[NC_012920.1] is a different mitochondrial sequence"));
+}
+
+#[test]
+fn reject_file() {
+    let twobit = reference("reject");
+    let vcf = write_vcf(
+        "reject",
+        &[],
+        &[
+            "1\t2\t.\tC\tG,Z\t.\t.\t.",
+            "chr9\t2\t.\tC\tG\t.\t.\t.",
+            "1\tx\t.\tC\tG\t.\t.\t.",
+            "1\t100\t.\tC\tG\t.\t.\t.",
+        ],
+    );
+    let rejects = std::env::temp_dir().join(format!("spdi-cli-{}-rejects.tsv", std::process::id()));
+    let output = spdi(&[
+        "annotate-vcf", "-t", &twobit, "-f", vcf.to_str().unwrap(), "--reject-file", rejects.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+    let content = std::fs::read_to_string(&rejects).unwrap();
+    let kinds: Vec<(&str, &str)> = content
+        .lines()
+        .map(|line| {
+            let words: Vec<&str> = line.split('\t').collect();
+            (words[3], words[4])
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            ("ALT", "KIND"),
+            ("Z", "InvalidBase"),
+            ("G", "UnknownSequence"),
+            ("G", "InvalidPosition"),
+            ("G", "OutOfBounds"),
+        ],
+        kinds
+    );
+    let output = spdi(&[
+        "annotate-vcf", "-t", &twobit, "-f", vcf.to_str().unwrap(), "--reject-file", rejects.to_str().unwrap(),
+        "--reject-format", "jsonl",
+    ]);
+    assert!(output.status.success());
+    let content = std::fs::read_to_string(&rejects).unwrap();
+    let first: serde_json::Value = serde_json::from_str(content.lines().next().unwrap()).unwrap();
+    assert_eq!(
+        serde_json::json!({
+            "chrom": "1", "pos": 2, "ref": "C", "alt": "Z", "kind": "InvalidBase", "message": "Wrong base: Z",
+        }),
+        first
+    );
+    assert_eq!(4, content.lines().count());
+}
+
+#[test]
+fn summary_file() {
+    let twobit = reference("summary");
+    let vcf = write_vcf(
+        "summary",
+        &[],
+        &[
+            "1\t2\t.\tC\tG,Z\t.\t.\t.",
+            "1\t6\t.\tATTA\tA,*\t.\t.\t.",
+            "chr9\t2\t.\tC\tG\t.\t.\t.",
+        ],
+    );
+    let summary = std::env::temp_dir().join(format!("spdi-cli-{}-summary.json", std::process::id()));
+    let output = spdi(&["annotate-vcf", "-t", &twobit, "-f", vcf.to_str().unwrap(), "--summary-file", summary.to_str().unwrap()]);
+    assert!(output.status.success());
+    let summary: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&summary).unwrap()).unwrap();
+    assert_eq!(3, summary["records"]);
+    assert_eq!(5, summary["alleles"]);
+    assert_eq!(1, summary["unsupported_alleles"]);
+    assert_eq!(serde_json::json!({"deletion": 1, "snv": 1}), summary["classes"]);
+    assert_eq!(serde_json::json!({"chr9": 1}), summary["unsupported_contigs"]);
+    assert_eq!(serde_json::json!({"InvalidBase": 1, "UnknownSequence": 1}), summary["errors"]);
+    assert_eq!(1, summary["shifted_alleles"]);
+    assert_eq!(6, summary["max_expansion"]);
+    assert_eq!("chr1:4:TTATTATTA:TTATTA", summary["max_expansion_allele"]["spdi"]);
+    // - writes the summary to standard error.
+    let output = spdi(&["annotate-vcf", "-t", &twobit, "-f", vcf.to_str().unwrap(), "--summary-file", "-"]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("\"records\": 3"));
+}
+
+#[test]
+fn exit_codes() {
+    let twobit = reference("exit");
+    let vcf = write_vcf(
+        "exit",
+        &[],
+        &[
+            "1\t2\t.\tC\tG,*\t.\t.\t.",
+            "1\t2\t.\tC\tZ\t.\t.\t.",
+            "chr9\t2\t.\tC\tG\t.\t.\t.",
+            "1\t100\t.\tC\tG\t.\t.\t.",
+            "1\t2\t.\tC\t<DEL>\t.\t.\t.",
+        ],
+    );
+    let vcf = vcf.to_str().unwrap();
+    let code = |args: &[&str]| spdi(args).status.code();
+    assert_eq!(Some(0), code(&["annotate-vcf", "-t", &twobit, "-f", vcf]));
+    assert_eq!(Some(0), code(&["annotate-vcf", "-t", &twobit, "-f", vcf, "--lenient"]));
+    assert_eq!(Some(2), code(&["annotate-vcf", "-t", &twobit]));
+    assert_eq!(Some(2), code(&["annotate-vcf", "-t", &twobit, "-f", vcf, "--strict", "--max-errors", "1"]));
+    assert_eq!(Some(3), code(&["annotate-vcf", "-t", &twobit, "-f", "/nonexistent/in.vcf"]));
+    assert_eq!(Some(4), code(&["annotate-vcf", "-t", "/nonexistent/ref.2bit", "-f", vcf]));
+    assert_eq!(Some(4), code(&["annotate-vcf", "-t", &twobit, "-f", vcf, "--contig-naming", "refseq"]));
+    let annotated = write_file("exit.annotated.vcf", &stdout(&spdi(&["annotate-vcf", "-t", &twobit, "-f", vcf])));
+    assert_eq!(Some(5), code(&["annotate-vcf", "-t", &twobit, "-f", annotated.to_str().unwrap(), "--existing", "fail"]));
+    assert_eq!(Some(5), code(&["convert", "-t", &twobit, "chr1:2:C"]));
+    assert_eq!(Some(6), code(&["convert", "-t", &twobit, "chr1:100:C:G"]));
+    assert_eq!(Some(6), code(&["annotate-vcf", "-t", &twobit, "-f", vcf, "--strict"]));
+}
+
+#[test]
+fn max_errors() {
+    let twobit = reference("max-errors");
+    // Errors: Z, chr9 and position 100. * and <DEL> are not errors.
+    let vcf = write_vcf(
+        "max-errors",
+        &[],
+        &[
+            "1\t2\t.\tC\tG,*\t.\t.\t.",
+            "1\t2\t.\tC\tZ\t.\t.\t.",
+            "chr9\t2\t.\tC\tG\t.\t.\t.",
+            "1\t2\t.\tC\t<DEL>\t.\t.\t.",
+            "1\t100\t.\tC\tG\t.\t.\t.",
+            "1\t2\t.\tC\tT\t.\t.\t.",
+        ],
+    );
+    let records = |output: &Output| stdout(output).lines().filter(|line| !line.starts_with('#')).count();
+    let output = spdi(&["annotate-vcf", "-t", &twobit, "-f", vcf.to_str().unwrap(), "--strict"]);
+    assert_eq!(Some(6), output.status.code());
+    assert_eq!(1, records(&output));
+    let output = spdi(&["annotate-vcf", "-t", &twobit, "-f", vcf.to_str().unwrap(), "--max-errors", "1"]);
+    assert_eq!(Some(6), output.status.code());
+    assert_eq!(2, records(&output));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Stopped after 2 alleles could not be converted."));
+    let output = spdi(&["annotate-vcf", "-t", &twobit, "-f", vcf.to_str().unwrap(), "--max-errors", "3"]);
+    assert_eq!(Some(0), output.status.code());
+    assert_eq!(6, records(&output));
+}
+
+#[test]
+fn convert_and_compare() {
+    let twobit = reference("convert");
+    let output = spdi(&["convert", "-t", &twobit, "chr1:2:C:G", "chr1:7:TTA:-"]);
+    assert!(output.status.success());
+    assert_eq!("chr1:2:C:G\nchr1:4:TTATTATTA:TTATTA\n", stdout(&output));
+    // Only - itself is an empty allele.
+    let output = spdi(&["convert", "-t", &twobit, "chr1:2:C:-A"]);
+    assert_eq!(Some(6), output.status.code());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Wrong base: -"));
+    let output = spdi(&["compare", "-t", &twobit, "chr1:4:TTA:-", "chr1:7:TTA:-"]);
+    assert_eq!(Some(0), output.status.code());
+    let output = spdi(&["compare", "-t", &twobit, "chr1:4:TTA:-", "chr1:2:C:G"]);
+    assert_eq!(Some(1), output.status.code());
+}
+
+#[test]
+fn to_vcf() {
+    let twobit = reference("to-vcf");
+    let output = spdi(&["to-vcf", "-t", &twobit, "chr1:3:TTA:-", "chr1:2:C:G"]);
+    assert!(output.status.success());
+    assert_eq!(
+        vec!["chr1\t2\tchr1:3:TTA:-\tCTTA\tC\t.\t.\t.", "chr1\t2\tchr1:2:C:G\tC\tG\t.\t.\t."],
+        lines_with(&stdout(&output), "chr1\t")
+    );
+    // SPDIs without an empty allele do not need the reference.
+    let output = spdi(&["to-vcf", "chr1:2:C:G"]);
+    assert!(output.status.success());
+    assert_eq!(Some(2), spdi(&["to-vcf", "chr1:3:TTA:-"]).status.code());
+    let output = spdi(&["to-vcf", "-t", &twobit, "chr1:2:C:--"]);
+    assert_eq!(Some(5), output.status.code());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Wrong base: -"));
+}
+
+#[test]
+fn validate_and_stats() {
+    let twobit = reference("validate");
+    let vcf = write_vcf("validate", &[], &["1\t2\t.\tA\tG\t.\t.\t.", "1\t2\t.\tC\tG\t.\t.\t."]);
+    let output = spdi(&["validate", "-t", &twobit, "-f", vcf.to_str().unwrap()]);
+    assert_eq!(Some(4), output.status.code());
+    assert_eq!(vec!["1\t2\tA\tG\tReferenceMismatch"], {
+        let out = stdout(&output);
+        out.lines()
+            .skip(1)
+            .map(|line| line.split('\t').take(5).collect::<Vec<&str>>().join("\t"))
+            .collect::<Vec<String>>()
+    });
+    let output = spdi(&["stats", "-t", &twobit, "-f", vcf.to_str().unwrap()]);
+    assert!(output.status.success());
+    let summary: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(2, summary["records"]);
+    assert_eq!(serde_json::json!({"snv": 2}), summary["classes"]);
+}
+
+#[test]
+fn convert_batch() {
+    let twobit = reference("batch");
+    let input = write_file("batch.txt", "chr1:2:C:G\nchr1:7:TTA:-\nbad\nchr1:2:C:-A\n");
+    let output = spdi(&["convert", "-t", &twobit, "-i", input.to_str().unwrap()]);
+    assert_eq!(Some(6), output.status.code());
+    assert_eq!(
+        "chr1:2:C:G\tchr1:2:C:G\nchr1:7:TTA:-\tchr1:4:TTATTATTA:TTATTA\nbad\t.\nchr1:2:C:-A\t.\n",
+        stdout(&output)
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+    assert!(stderr.contains("Line 3: Wrong input format: [bad]"));
+    assert!(stderr.contains("Line 4: Wrong base: -"));
+    // Columns of a TSV file read from standard input
+    let mut child = Command::new(env!("CARGO_BIN_EXE_spdi"))
+        .args(["convert", "-t", &twobit, "-i", "-", "--columns", "1,2,4,5", "--header"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::io::Write::write_all(&mut child.stdin.take().unwrap(), b"chrom\tpos\tid\tref\talt\nchr1\t2\tx\tC\tG\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert_eq!("chrom\tpos\tid\tref\talt\tSPDI\nchr1\t2\tx\tC\tG\tchr1:2:C:G\n", stdout(&output));
+}
+
+#[test]
+fn convert_json() {
+    let twobit = reference("json");
+    let output = spdi(&["convert", "-t", &twobit, "--format", "ndjson", "chr1:7:TTA:-", "chr1:2:C:Z"]);
+    assert_eq!(Some(6), output.status.code());
+    let stdout = stdout(&output);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(2, lines.len());
+    assert!(lines[0].contains("\"spdi\":\"chr1:4:TTATTATTA:TTATTA\""));
+    assert!(lines[0].contains("\"class\":\"deletion\",\"shiftable\":true,\"repeat\":{\"motif\":\"TTA\",\"period\":3,\"ref_copies\":3,\"alt_copies\":2},\"left_shift\":3,\"right_shift\":3,\"trimmed_left\":0,\"trimmed_right\":0,\"error\":null"));
+    assert!(lines[1].contains("\"spdi\":null"));
+    assert!(lines[1].contains("\"error\":{\"kind\":\"InvalidBase\",\"message\":\"Wrong base: Z\"}"));
+    let output = spdi(&["convert", "-t", &twobit, "--format", "json", "chr1:2:C:G"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    assert!(stdout.starts_with("[\n{\"input\":\"chr1:2:C:G\""));
+    assert!(stdout.ends_with("}\n]\n"));
+}